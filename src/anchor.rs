@@ -0,0 +1,160 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Commitments for anchoring a chain in an external ledger.
+//!
+//! A commitment is `H(tag || genesis hash || entry count || tip hash)` with the
+//! configured hasher and the count as a u64 in the configured `endianness`,
+//! fitted to 32 bytes like Merkle nodes. Unlike the tip hash alone it also pins
+//! the length of the chain, so a published commitment cannot be matched by a
+//! truncated chain.
+//!
+//! Several chains, e.g. one per shard, are anchored together by
+//! `aggregate_roots`: the chains are ordered by `deterministic_id`, then by tip
+//! hash, and the leaves `id (u64 little-endian) || tip hash` are combined into a
+//! Merkle root as built by the `merkle` module. The aggregate is
+//! `H(tag || chain count as u64 little-endian || root)`, where the root is left
+//! out when there are no chains and is the single leaf hash for one chain.
+
+use alloc::vec::Vec;
+
+use digest::Digest;
+
+use crate::merkle::{hash_32, root_of};
+use crate::verify::hashes_equal;
+use crate::PoHUsage;
+
+/// Tag opening every commitment.
+const COMMITMENT_TAG: &[u8] = b"poh-yugen/commitment";
+/// Tag opening every aggregate of several chains.
+const AGGREGATE_TAG: &[u8] = b"poh-yugen/aggregate";
+
+impl<D: Digest + Clone> PoHUsage<D> {
+    /// Commitment to the genesis entry, the number of entries and the tip.
+    ///
+    /// The count includes pruned entries, and the genesis hash is kept when the
+    /// genesis entry is pruned, so pruning does not change the commitment.
+    #[must_use]
+    pub fn commitment(&self) -> [u8; 32] {
+        let tip = self.last().expect("PoH state always holds the genesis entry");
+        let count = tip.id.saturating_add(1);
+        hash_32(&self.config.hasher, &[COMMITMENT_TAG, self.genesis_hash(), &self.config.endianness.u64_bytes(count), &tip.hash])
+    }
+
+    /// Whether `commitment` is the current commitment of this chain.
+    #[must_use]
+    pub fn verify_commitment(&self, commitment: &[u8; 32]) -> bool {
+        hashes_equal(&self.commitment(), commitment)
+    }
+}
+
+/// Aggregate commitment to the current tips of `chains`, hashed with a fresh `D`.
+///
+/// The order of `chains` does not matter: leaves are ordered by the numeric value
+/// of `deterministic_id`, then by tip hash, not by their encoded bytes.
+#[must_use]
+pub fn aggregate_roots<D: Digest + Clone>(chains: &[&PoHUsage<D>]) -> [u8; 32] {
+    let hasher = D::new();
+    let mut tips: Vec<(u64, &[u8])> =
+        chains.iter().map(|chain| (chain.deterministic_id(), chain.tip().expect("PoH state always holds the genesis entry"))).collect();
+    tips.sort_unstable();
+    let leaves: Vec<Vec<u8>> = tips.iter().map(|(id, tip)| [&id.to_le_bytes()[..], tip].concat()).collect();
+    let count = (leaves.len() as u64).to_le_bytes();
+    match root_of(&hasher, leaves.iter().map(Vec::as_slice)) {
+        Some(root) => hash_32(&hasher, &[AGGREGATE_TAG, &count, &root]),
+        None => hash_32(&hasher, &[AGGREGATE_TAG, &count]),
+    }
+}
+
+/// Whether `aggregate` is the current `aggregate_roots` of `chains`.
+#[must_use]
+pub fn verify_aggregate<D: Digest + Clone>(chains: &[&PoHUsage<D>], aggregate: &[u8; 32]) -> bool {
+    hashes_equal(&aggregate_roots(chains), aggregate)
+}
+
+#[test]
+fn commitments_pin_the_chain_length() {
+    use crate::{InitialSeed, PoHConfig, TickEntryType};
+
+    let config = PoHConfig::new(sha2::Sha256::new(), 32, 10, Some(4), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed([52; 64]), None, vec![]).unwrap();
+    poh.init().unwrap();
+    let commitment = poh.commitment();
+    assert!(poh.verify_commitment(&commitment));
+
+    let fork = poh.fork(3).unwrap();
+    assert!(!fork.verify_commitment(&commitment));
+    let mut other = poh.clone();
+    other.state[4].hash[0] ^= 1;
+    assert!(!other.verify_commitment(&commitment));
+    poh.resume(0).unwrap();
+    assert!(poh.verify_commitment(&commitment));
+}
+
+#[test]
+fn commitments_survive_pruning() {
+    use crate::{InitialSeed, PoHConfig, TickEntryType};
+
+    let config = PoHConfig::new(sha2::Sha256::new(), 32, 10, Some(6), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config.clone(), InitialSeed([88; 64]), None, vec![]).unwrap();
+    poh.resume(4).unwrap();
+    let (commitment, fingerprint) = (poh.commitment(), poh.fingerprint());
+
+    let mut pruned = poh.clone();
+    pruned.prune_before(2).unwrap();
+    pruned.prune_before(1).unwrap();
+    assert!(pruned.verify_commitment(&commitment));
+    assert_eq!(pruned.fingerprint(), fingerprint);
+    assert!(pruned.same_genesis(&poh));
+
+    let restored = PoHUsage::from_bytes(&pruned.to_bytes().unwrap(), config.clone()).unwrap();
+    assert!(restored.verify_commitment(&commitment));
+    let resumed = PoHUsage::from_snapshot(poh.snapshot(), config.clone(), vec![]).unwrap();
+    assert!(resumed.verify_commitment(&commitment));
+
+    let windowed = PoHConfig { window: Some(2), ..config };
+    let mut windowed = PoHUsage::new(windowed, InitialSeed([88; 64]), None, vec![]).unwrap();
+    windowed.resume(4).unwrap();
+    assert!(windowed.verify_commitment(&commitment));
+}
+
+#[test]
+fn aggregates_cover_every_chain() {
+    use crate::{InitialSeed, PoHConfig, TickEntryType};
+
+    let config = PoHConfig::new(sha2::Sha256::new(), 32, 10, Some(4), true, true, TickEntryType::Data);
+    let mut a = PoHUsage::new(config.clone(), InitialSeed([74; 64]), None, vec![]).unwrap();
+    let b = PoHUsage::new(config, InitialSeed([75; 64]), None, vec![]).unwrap();
+    a.resume(2).unwrap();
+
+    let aggregate = aggregate_roots(&[&a, &b]);
+    assert_eq!(aggregate_roots(&[&b, &a]), aggregate);
+    assert!(verify_aggregate(&[&a, &b], &aggregate));
+    assert!(!verify_aggregate(&[&a], &aggregate));
+
+    let single = aggregate_roots(&[&a]);
+    assert_ne!(single, aggregate);
+    let empty = aggregate_roots::<sha2::Sha256>(&[]);
+    assert_ne!(empty, single);
+    assert!(verify_aggregate::<sha2::Sha256>(&[], &empty));
+
+    a.resume(1).unwrap();
+    assert!(!verify_aggregate(&[&a, &b], &aggregate));
+}
+
+#[test]
+fn aggregates_order_chains_by_numeric_id() {
+    use crate::{InitialSeed, PoHConfig, TickEntryType};
+
+    let config = PoHConfig::new(sha2::Sha256::new(), 32, 10, Some(4), true, true, TickEntryType::Data);
+    let chains: Vec<_> = (0..8u8).map(|seed| PoHUsage::new(config.clone(), InitialSeed([seed; 64]), None, vec![]).unwrap()).collect();
+    let mut sorted: Vec<&PoHUsage<_>> = chains.iter().collect();
+    sorted.sort_by_key(|chain| chain.deterministic_id());
+    let mut by_bytes = sorted.clone();
+    by_bytes.sort_by_key(|chain| chain.deterministic_id().to_le_bytes());
+    assert_ne!(by_bytes.iter().map(|chain| chain.deterministic_id()).collect::<Vec<_>>(), sorted.iter().map(|chain| chain.deterministic_id()).collect::<Vec<_>>());
+
+    let hasher = sha2::Sha256::new();
+    let leaves: Vec<Vec<u8>> = sorted.iter().map(|chain| [&chain.deterministic_id().to_le_bytes()[..], chain.tip().unwrap()].concat()).collect();
+    let root = root_of(&hasher, leaves.iter().map(Vec::as_slice)).unwrap();
+    assert_eq!(aggregate_roots(&chains.iter().collect::<Vec<_>>()), hash_32(&hasher, &[AGGREGATE_TAG, &8u64.to_le_bytes(), &root]));
+}
@@ -0,0 +1,499 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Compact binary encoding of PoH chains.
+//!
+//! The layout is stable across crate versions. All integers are little-endian.
+//!
+//! ```text
+//! magic        4 bytes   "POHY"
+//! version      u8        7
+//! output size  u32       length of every entry hash
+//! id           u64       id of the PoH instance
+//! extensions   u16 count, then one tag byte per extension
+//!              (`Breaker` is followed by its u64 interval)
+//! data rounds  u8 flag, then u64 if set: hash rounds since the last data entry
+//!              as of the first entry, when a data entry came before it
+//! last nonce   u8 flag, then u64 if set: nonce of the last data entry up to the
+//!              first entry
+//! pruned       u8 flag, then the 32-byte pruning commitment if set
+//! genesis      u8 flag, then output size bytes if set: hash of the genesis
+//!              entry once it is no longer held
+//! entry count  u64
+//! entries:
+//!   id         u64
+//!   flags      u8        bit 0 timestamp, bit 1 fingerprint, bit 2 appended data,
+//!                        bit 3 breaker, bit 4 milestones, bit 5 RFC 3339 timestamp,
+//!                        bit 6 previous hash, bit 7 extended flags
+//!   extended   u8        bit 0 interval, bit 1 data lane, bit 2 nonce,
+//!                        bit 3 event, whose hash is the appended data (if flagged)
+//!   hash       output size bytes
+//!   prev       u32 length + bytes   (if flagged)
+//!   interval   u64                  (if flagged)
+//!   timestamp  u64                  (if flagged)
+//!   RFC 3339   u32 length + UTF-8   (if flagged)
+//!   fingerprint u32 length + UTF-8  (if flagged)
+//!   data       u32 length + bytes   (if flagged)
+//!   lane       u8                   (if flagged)
+//!   nonce      u64                  (if flagged)
+//!   milestones u16 count, then u32 length + UTF-8 per name (if flagged)
+//! checksum     digest of every preceding byte, with the configured hasher
+//! ```
+//!
+//! Appended data is always encoded in its original, uncompressed form, and is
+//! compressed again on decoding when `compress_data` is set.
+//!
+//! The checksum catches truncated or corrupted files before the chain is
+//! replayed. Fields whose length does not fit their length prefix are rejected
+//! with `PoHError::Encode` rather than truncated.
+//!
+//! The version is bumped whenever the layout changes. Only the current version
+//! is decoded; files of any other version are rejected.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use digest::Digest;
+
+use crate::errors::PoHError;
+use crate::extensions::Extensions;
+use crate::{AppendedData, EntryHash, EventHash, PoHConfig, PoHEntry, PoHUsage};
+
+/// Magic number opening every encoded chain.
+pub const MAGIC: [u8; 4] = *b"POHY";
+/// Current version of the binary format.
+pub const FORMAT_VERSION: u8 = 7;
+
+const FLAG_TIMESTAMP: u8 = 1 << 0;
+const FLAG_FINGERPRINT: u8 = 1 << 1;
+const FLAG_DATA: u8 = 1 << 2;
+const FLAG_BREAKER: u8 = 1 << 3;
+const FLAG_MILESTONES: u8 = 1 << 4;
+const FLAG_TIMESTAMP_RFC3339: u8 = 1 << 5;
+const FLAG_PREV: u8 = 1 << 6;
+const FLAG_EXTENDED: u8 = 1 << 7;
+
+const EXT_INTERVAL: u8 = 1 << 0;
+const EXT_LANE: u8 = 1 << 1;
+const EXT_NONCE: u8 = 1 << 2;
+const EXT_EVENT: u8 = 1 << 3;
+const KNOWN_EXTENDED: u8 = EXT_INTERVAL | EXT_LANE | EXT_NONCE | EXT_EVENT;
+
+impl<D: Digest + Clone> PoHUsage<D> {
+    /// Encodes the chain into the compact binary format described in the module docs.
+    ///
+    /// Fails with `PoHError::Encode` when a field is too long for its length
+    /// prefix, e.g. appended data of 4 GiB or more.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, PoHError> {
+        let output_size = self.state.first().map_or(self.config.hash_len(), |entry| entry.hash.len());
+
+        let mut out = Vec::new();
+        out.extend_from_slice(&MAGIC);
+        out.push(FORMAT_VERSION);
+        out.extend_from_slice(&length::<u32>(output_size, "output size")?.to_le_bytes());
+        out.extend_from_slice(&self.id.to_le_bytes());
+        out.extend_from_slice(&length::<u16>(self.extensions.len(), "too many extensions")?.to_le_bytes());
+        for extension in &self.extensions {
+            encode_extension(&mut out, extension);
+        }
+        write_optional(&mut out, self.data_rounds);
+        write_optional(&mut out, self.last_nonce);
+        match &self.pruned_commitment {
+            Some(commitment) => {
+                out.push(1);
+                out.extend_from_slice(commitment);
+            }
+            None => out.push(0),
+        }
+        match &self.genesis_hash {
+            Some(hash) => {
+                out.push(1);
+                out.extend_from_slice(hash);
+            }
+            None => out.push(0),
+        }
+        out.extend_from_slice(&(self.state.len() as u64).to_le_bytes());
+        for entry in &self.state {
+            encode_entry(&mut out, entry)?;
+        }
+        let checksum = self.config.hasher.clone().chain_update(&out).finalize();
+        out.extend_from_slice(&checksum);
+        Ok(out)
+    }
+
+    /// Decodes a chain produced by `to_bytes`.
+    ///
+    /// The checksum is checked first and a mismatch, including one caused by
+    /// decoding with a different hasher, is reported as `PoHError::Corrupt`. The
+    /// encoded hash length must match `config`. The chain is not replayed; call
+    /// `verify` on the result to check it. The instance keeps the encoded id, and
+    /// duplicate or conflicting extensions are handled as in `new`.
+    pub fn from_bytes(bytes: &[u8], config: PoHConfig<D>) -> Result<Self, PoHError> {
+        config.validate()?;
+        let mut reader = Reader { bytes };
+
+        if reader.take(4)? != MAGIC {
+            return Err(PoHError::Decode("missing magic number"));
+        }
+        if reader.u8()? != FORMAT_VERSION {
+            return Err(PoHError::Decode("unsupported format version"));
+        }
+        let checksum_len = <D as Digest>::output_size();
+        let body_len = reader.bytes.len().checked_sub(checksum_len).ok_or(PoHError::Corrupt)?;
+        let (rest, checksum) = reader.bytes.split_at(body_len);
+        let expected = config.hasher.clone().chain_update(&bytes[..bytes.len() - checksum_len]).finalize();
+        if !crate::verify::hashes_equal(&expected, checksum) {
+            return Err(PoHError::Corrupt);
+        }
+        reader.bytes = rest;
+        let output_size = reader.u32()? as usize;
+        if output_size != config.hash_len() {
+            return Err(PoHError::OutputSizeMismatch { configured: config.hash_len(), hasher: output_size });
+        }
+        let id = reader.u64()?;
+
+        let extension_count = reader.u16()?;
+        let mut extensions = Vec::with_capacity(extension_count as usize);
+        for _ in 0..extension_count {
+            extensions.push(decode_extension(&mut reader)?);
+        }
+        crate::extensions::normalize(&mut extensions)?;
+        let data_rounds = reader.optional()?;
+        let last_nonce = reader.optional()?;
+        let pruned_commitment = match reader.u8()? {
+            0 => None,
+            1 => Some(reader.take(32)?.try_into().expect("32 bytes")),
+            _ => return Err(PoHError::Decode("invalid pruning commitment flag")),
+        };
+        let genesis_hash = match reader.u8()? {
+            0 => None,
+            1 => Some(EntryHash::new(reader.take(output_size)?.to_vec(), output_size)?),
+            _ => return Err(PoHError::Decode("invalid genesis hash flag")),
+        };
+
+        let entry_count = reader.u64()?;
+        if entry_count == 0 {
+            return Err(PoHError::Decode("chain has no entries"));
+        }
+        let mut state = Vec::new();
+        for _ in 0..entry_count {
+            state.push(decode_entry(&mut reader, output_size)?);
+        }
+        if !reader.bytes.is_empty() {
+            return Err(PoHError::Decode("trailing bytes after the last entry"));
+        }
+
+        #[cfg(feature = "compression")]
+        for entry in &mut state {
+            entry.appended_data = crate::compression::store(config.compress_data, entry.appended_data.take());
+        }
+
+        let mut poh = Self::from_parts(config, state, extensions);
+        poh.id = id;
+        poh.data_rounds = data_rounds;
+        poh.last_nonce = last_nonce;
+        poh.pruned_commitment = pruned_commitment;
+        poh.genesis_hash = genesis_hash;
+        Ok(poh)
+    }
+}
+
+/// Extended flags `encode_entry` sets for `entry`.
+fn entry_extended_flags(entry: &PoHEntry) -> u8 {
+    let mut extended = 0;
+    if entry.interval != 0 {
+        extended |= EXT_INTERVAL;
+    }
+    if entry.appended_data.as_ref().is_some_and(|data| data.lane() != 0) {
+        extended |= EXT_LANE;
+    }
+    if entry.nonce.is_some() {
+        extended |= EXT_NONCE;
+    }
+    if entry.event.is_some() && entry.appended_data.is_some() {
+        extended |= EXT_EVENT;
+    }
+    extended
+}
+
+/// Appends the record of `entry`, failing with `PoHError::Encode` when a field
+/// does not fit its length prefix.
+pub(crate) fn encode_entry(out: &mut Vec<u8>, entry: &PoHEntry) -> Result<(), PoHError> {
+    let mut flags = 0;
+    if entry.timestamp.is_some() {
+        flags |= FLAG_TIMESTAMP;
+    }
+    if entry.fingerprint.is_some() {
+        flags |= FLAG_FINGERPRINT;
+    }
+    if entry.appended_data.is_some() {
+        flags |= FLAG_DATA;
+    }
+    if entry.breaker {
+        flags |= FLAG_BREAKER;
+    }
+    if !entry.milestones.is_empty() {
+        flags |= FLAG_MILESTONES;
+    }
+    if entry.timestamp_rfc3339.is_some() {
+        flags |= FLAG_TIMESTAMP_RFC3339;
+    }
+    if entry.prev.is_some() {
+        flags |= FLAG_PREV;
+    }
+    let lane = entry.appended_data.as_ref().map_or(0, AppendedData::lane);
+    let extended = entry_extended_flags(entry);
+    if extended != 0 {
+        flags |= FLAG_EXTENDED;
+    }
+
+    out.extend_from_slice(&entry.id.to_le_bytes());
+    out.push(flags);
+    if extended != 0 {
+        out.push(extended);
+    }
+    out.extend_from_slice(&entry.hash);
+    if let Some(prev) = &entry.prev {
+        write_blob(out, prev)?;
+    }
+    if entry.interval != 0 {
+        out.extend_from_slice(&entry.interval.to_le_bytes());
+    }
+    if let Some(timestamp) = entry.timestamp {
+        out.extend_from_slice(&timestamp.to_le_bytes());
+    }
+    if let Some(timestamp) = &entry.timestamp_rfc3339 {
+        write_blob(out, timestamp.as_bytes())?;
+    }
+    if let Some(fingerprint) = &entry.fingerprint {
+        write_blob(out, fingerprint.as_bytes())?;
+    }
+    if let Some(data) = &entry.appended_data {
+        write_blob(out, &data.get_data())?;
+    }
+    if lane != 0 {
+        out.push(lane);
+    }
+    if let Some(nonce) = entry.nonce {
+        out.extend_from_slice(&nonce.to_le_bytes());
+    }
+    if !entry.milestones.is_empty() {
+        out.extend_from_slice(&length::<u16>(entry.milestones.len(), "too many milestones on one entry")?.to_le_bytes());
+        for name in &entry.milestones {
+            write_blob(out, name.as_bytes())?;
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn decode_entry(reader: &mut Reader<'_>, output_size: usize) -> Result<PoHEntry, PoHError> {
+    let id = reader.u64()?;
+    let flags = reader.u8()?;
+    let extended = if flags & FLAG_EXTENDED != 0 { reader.u8()? } else { 0 };
+    if extended & !KNOWN_EXTENDED != 0 {
+        return Err(PoHError::Decode("unknown entry flags"));
+    }
+    let hash = EntryHash::new(reader.take(output_size)?.to_vec(), output_size)?;
+    let prev = if flags & FLAG_PREV != 0 { Some(reader.blob()?.to_vec()) } else { None };
+    let interval = if extended & EXT_INTERVAL != 0 { reader.u64()? } else { 0 };
+    let timestamp = if flags & FLAG_TIMESTAMP != 0 { Some(reader.u64()?) } else { None };
+    let timestamp_rfc3339 = if flags & FLAG_TIMESTAMP_RFC3339 != 0 { Some(reader.string()?) } else { None };
+    let fingerprint = if flags & FLAG_FINGERPRINT != 0 { Some(reader.string()?) } else { None };
+    let mut appended_data = if flags & FLAG_DATA != 0 { Some(AppendedData::new(reader.blob()?.to_vec())) } else { None };
+    if extended & EXT_LANE != 0 {
+        let data = appended_data.as_mut().ok_or(PoHError::Decode("lane without appended data"))?;
+        data.lane = reader.u8()?;
+    }
+    let nonce = if extended & EXT_NONCE != 0 { Some(reader.u64()?) } else { None };
+    let event = if extended & EXT_EVENT != 0 {
+        let data = appended_data.as_ref().ok_or(PoHError::Decode("event without appended data"))?;
+        let hash = data.get_data().as_ref().try_into().map_err(|_| PoHError::Decode("event hash is not 32 bytes"))?;
+        Some(EventHash { hash })
+    } else {
+        None
+    };
+
+    let breaker = flags & FLAG_BREAKER != 0;
+    let mut milestones = Vec::new();
+    if flags & FLAG_MILESTONES != 0 {
+        for _ in 0..reader.u16()? {
+            milestones.push(reader.string()?);
+        }
+    }
+
+    Ok(PoHEntry { id, timestamp, timestamp_rfc3339, fingerprint, hash, prev, appended_data, breaker, milestones, interval, nonce, event })
+}
+
+fn write_blob(out: &mut Vec<u8>, blob: &[u8]) -> Result<(), PoHError> {
+    out.extend_from_slice(&length::<u32>(blob.len(), "field longer than u32::MAX bytes")?.to_le_bytes());
+    out.extend_from_slice(blob);
+    Ok(())
+}
+
+/// Writes a flag byte, followed by `value` if it is set.
+fn write_optional(out: &mut Vec<u8>, value: Option<u64>) {
+    match value {
+        Some(value) => {
+            out.push(1);
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+        None => out.push(0),
+    }
+}
+
+/// `len` as a length prefix of type `T`, or `PoHError::Encode(reason)`.
+fn length<T: TryFrom<usize>>(len: usize, reason: &'static str) -> Result<T, PoHError> {
+    T::try_from(len).map_err(|_| PoHError::Encode(reason))
+}
+
+fn encode_extension(out: &mut Vec<u8>, extension: &Extensions) {
+    match extension {
+        Extensions::TickHasher => out.push(0),
+        Extensions::Breaker(interval) => {
+            out.push(1);
+            out.extend_from_slice(&interval.to_le_bytes());
+        }
+        Extensions::MerkleTree => out.push(2),
+        Extensions::Timestamp => out.push(3),
+        Extensions::TimestampRFC3339 => out.push(4),
+        Extensions::Milestone => out.push(5),
+    }
+}
+
+fn decode_extension(reader: &mut Reader<'_>) -> Result<Extensions, PoHError> {
+    match reader.u8()? {
+        0 => Ok(Extensions::TickHasher),
+        1 => Ok(Extensions::Breaker(reader.u64()?)),
+        2 => Ok(Extensions::MerkleTree),
+        3 => Ok(Extensions::Timestamp),
+        4 => Ok(Extensions::TimestampRFC3339),
+        5 => Ok(Extensions::Milestone),
+        _ => Err(PoHError::Decode("unknown extension tag")),
+    }
+}
+
+/// Cursor over the bytes being decoded.
+pub(crate) struct Reader<'a> {
+    pub(crate) bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], PoHError> {
+        if self.bytes.len() < len {
+            return Err(PoHError::Decode("unexpected end of input"));
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+    fn u8(&mut self) -> Result<u8, PoHError> {
+        Ok(self.take(1)?[0])
+    }
+    fn u16(&mut self) -> Result<u16, PoHError> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().expect("two bytes")))
+    }
+    fn u32(&mut self) -> Result<u32, PoHError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().expect("four bytes")))
+    }
+    fn u64(&mut self) -> Result<u64, PoHError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().expect("eight bytes")))
+    }
+    fn optional(&mut self) -> Result<Option<u64>, PoHError> {
+        match self.u8()? {
+            0 => Ok(None),
+            1 => Ok(Some(self.u64()?)),
+            _ => Err(PoHError::Decode("invalid optional field flag")),
+        }
+    }
+    fn blob(&mut self) -> Result<&'a [u8], PoHError> {
+        let len = self.u32()? as usize;
+        self.take(len)
+    }
+    fn string(&mut self) -> Result<String, PoHError> {
+        let bytes = self.blob()?.to_vec();
+        String::from_utf8(bytes).map_err(|_| PoHError::Decode("string is not valid UTF-8"))
+    }
+}
+
+#[test]
+fn binary_round_trip_is_lossless() {
+    use crate::{InitialSeed, TickEntryType};
+
+    let config = PoHConfig::new(sha2::Sha256::new(), 32, 10, Some(5), true, true, TickEntryType::UTF8String);
+    let extensions = vec![Extensions::MerkleTree, Extensions::Breaker(2), Extensions::Milestone, Extensions::TimestampRFC3339];
+    let mut poh = PoHUsage::new(config.clone(), InitialSeed([10; 64]), Some(b"genesis".to_vec()), extensions).unwrap();
+    poh.add_milestone("genesis".to_string()).unwrap();
+    poh.init().unwrap();
+
+    let bytes = poh.to_bytes().unwrap();
+    assert_eq!(&bytes[..4], &MAGIC);
+    let restored = PoHUsage::from_bytes(&bytes, config.clone()).unwrap();
+    assert_eq!(restored.state, poh.state);
+    assert_eq!(restored.extensions, poh.extensions);
+    assert_eq!(restored.id, poh.id);
+    assert_eq!(restored.milestones, poh.milestones);
+    assert!(restored.verify().is_ok());
+
+    assert_eq!(PoHUsage::from_bytes(&bytes[..bytes.len() - 1], config.clone()).err(), Some(PoHError::Corrupt));
+    let short = PoHConfig::new(sha2::Sha256::new(), 16, 10, Some(5), true, true, TickEntryType::Data);
+    assert!(matches!(PoHUsage::from_bytes(&bytes, short), Err(PoHError::OutputSizeMismatch { .. })));
+}
+
+#[test]
+fn checksum_rejects_corrupted_files() {
+    use crate::{InitialSeed, TickEntryType};
+
+    let config = PoHConfig::new(sha2::Sha256::new(), 32, 10, Some(3), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config.clone(), InitialSeed([43; 64]), None, vec![]).unwrap();
+    poh.init().unwrap();
+    let bytes = poh.to_bytes().unwrap();
+
+    for index in [5, 40, bytes.len() - 40, bytes.len() - 1] {
+        let mut corrupted = bytes.clone();
+        corrupted[index] ^= 1;
+        assert_eq!(PoHUsage::from_bytes(&corrupted, config.clone()).err(), Some(PoHError::Corrupt));
+    }
+    assert_eq!(PoHUsage::from_bytes(&bytes[..20], config).err(), Some(PoHError::Corrupt));
+}
+
+#[test]
+fn other_versions_are_rejected_and_lengths_are_checked() {
+    use crate::{InitialSeed, TickEntryType};
+
+    let config = PoHConfig::new(sha2::Sha256::new(), 32, 10, Some(3), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config.clone(), InitialSeed([82; 64]), None, vec![]).unwrap();
+    poh.append_data_with_nonce(b"tx".to_vec(), 7).unwrap();
+    let bytes = poh.to_bytes().unwrap();
+    assert_eq!(bytes[4], FORMAT_VERSION);
+    assert_eq!(PoHUsage::from_bytes(&bytes, config.clone()).unwrap().state, poh.state);
+
+    for version in [1, 2, FORMAT_VERSION + 1] {
+        let mut other = bytes[..bytes.len() - 32].to_vec();
+        other[4] = version;
+        let checksum = config.hasher.clone().chain_update(&other).finalize();
+        other.extend_from_slice(&checksum);
+        assert_eq!(PoHUsage::from_bytes(&other, config.clone()).err(), Some(PoHError::Decode("unsupported format version")));
+    }
+
+    poh.state[1].milestones = vec![String::new(); usize::from(u16::MAX) + 1];
+    assert_eq!(poh.to_bytes(), Err(PoHError::Encode("too many milestones on one entry")));
+}
+
+#[test]
+fn decoding_keeps_the_id_and_normalizes_extensions() {
+    use crate::{InitialSeed, TickEntryType};
+
+    let config = PoHConfig::new(sha2::Sha256::new(), 32, 10, Some(3), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config.clone(), InitialSeed([83; 64]), None, vec![]).unwrap();
+    let bytes = poh.to_bytes().unwrap();
+    let first = PoHUsage::from_bytes(&bytes, config.clone()).unwrap();
+    let second = PoHUsage::from_bytes(&bytes, config.clone()).unwrap();
+    assert_eq!(first.get_id(), poh.get_id());
+    assert_eq!(second.get_id(), poh.get_id());
+
+    poh.extensions = vec![Extensions::MerkleTree, Extensions::MerkleTree];
+    let decoded = PoHUsage::from_bytes(&poh.to_bytes().unwrap(), config.clone()).unwrap();
+    assert_eq!(decoded.extensions, vec![Extensions::MerkleTree]);
+
+    poh.extensions = vec![Extensions::Breaker(2), Extensions::Breaker(3)];
+    assert!(matches!(PoHUsage::from_bytes(&poh.to_bytes().unwrap(), config), Err(PoHError::ConflictingExtensions(..))));
+}
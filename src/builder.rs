@@ -0,0 +1,196 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Builder for `PoHConfig`.
+//!
+//! ```
+//! use poh_yugen::{PoHConfig, TickEntryType};
+//! use sha2::{Digest, Sha256};
+//!
+//! let config = PoHConfig::builder(Sha256::new())
+//!     .tick_interval(500)
+//!     .max_entries(Some(100))
+//!     .tick_entry_type(TickEntryType::Empty)
+//!     .allow_data_entries(false)
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(config.output_size, 32);
+//! ```
+
+use digest::Digest;
+use sha2::{Sha256, Sha512};
+
+use crate::errors::PoHError;
+use crate::{DataMixMode, Endianness, PoHConfig, TickEntryType};
+
+/// Hash rounds per entry used when no `tick_interval` is set.
+pub const DEFAULT_TICK_INTERVAL: u64 = 1000;
+
+/// Chainable builder for `PoHConfig`.
+///
+/// Defaults to the hasher's full output size, `DEFAULT_TICK_INTERVAL` rounds per
+/// entry, no entry limit, both data and empty entries allowed with no size limit,
+/// no stored previous hashes, data mixed in as a suffix, little-endian integers,
+/// and `TickEntryType::Data`.
+#[derive(Debug, Clone)]
+#[must_use = "the configuration is only created by build"]
+pub struct PoHConfigBuilder<D: Digest + Clone> {
+    config: PoHConfig<D>,
+}
+
+impl<D: Digest + Clone> PoHConfigBuilder<D> {
+    pub fn new(hasher: D) -> Self {
+        Self {
+            config: PoHConfig::new(
+                hasher,
+                <D as Digest>::output_size(),
+                DEFAULT_TICK_INTERVAL,
+                None,
+                true,
+                true,
+                TickEntryType::Data,
+            ),
+        }
+    }
+    pub fn output_size(mut self, output_size: usize) -> Self {
+        self.config.output_size = output_size;
+        self
+    }
+    pub fn tick_interval(mut self, tick_interval: u64) -> Self {
+        self.config.tick_interval = tick_interval;
+        self
+    }
+    pub fn max_entries(mut self, max_entries: Option<usize>) -> Self {
+        self.config.max_entries = max_entries;
+        self
+    }
+    pub fn allow_data_entries(mut self, allow: bool) -> Self {
+        self.config.allow_data_entries = allow;
+        self
+    }
+    pub fn allow_empty_entries(mut self, allow: bool) -> Self {
+        self.config.allow_empty_entries = allow;
+        self
+    }
+    pub fn max_data_len(mut self, max_data_len: Option<usize>) -> Self {
+        self.config.max_data_len = max_data_len;
+        self
+    }
+    pub fn store_prev_links(mut self, store: bool) -> Self {
+        self.config.store_prev_links = store;
+        self
+    }
+    pub fn data_mix(mut self, data_mix: DataMixMode) -> Self {
+        self.config.data_mix = data_mix;
+        self
+    }
+    #[cfg(feature = "compression")]
+    pub fn compress_data(mut self, compress_data: bool) -> Self {
+        self.config.compress_data = compress_data;
+        self
+    }
+    pub fn window(mut self, window: Option<usize>) -> Self {
+        self.config.window = window;
+        self
+    }
+    pub fn min_interval_between_data(mut self, rounds: u64) -> Self {
+        self.config.min_interval_between_data = rounds;
+        self
+    }
+    pub fn stretch_seed(mut self, stretch: bool) -> Self {
+        self.config.stretch_seed = stretch;
+        self
+    }
+    pub fn endianness(mut self, endianness: Endianness) -> Self {
+        self.config.endianness = endianness;
+        self
+    }
+    pub fn tick_entry_type(mut self, tick_entry_type: TickEntryType) -> Self {
+        self.config.tick_entry_type = tick_entry_type;
+        self
+    }
+    /// Validates the configuration and returns it.
+    pub fn build(self) -> Result<PoHConfig<D>, PoHError> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
+impl<D: Digest + Clone> PoHConfig<D> {
+    /// Starts building a configuration around `hasher`.
+    pub fn builder(hasher: D) -> PoHConfigBuilder<D> {
+        PoHConfigBuilder::new(hasher)
+    }
+}
+
+/// The recommended starting point: SHA-256 with the builder defaults.
+///
+/// `DEFAULT_TICK_INTERVAL` is a fixed round count, not a duration: how long it
+/// takes depends on the machine. Use `calibrate` to pick an interval for a
+/// target duration, or `PoHUsage::auto` to start a chain with one.
+impl Default for PoHConfig<Sha256> {
+    fn default() -> Self {
+        PoHConfigBuilder::new(Sha256::new()).config
+    }
+}
+
+impl PoHConfig<Sha256> {
+    /// A profile for tests and high-frequency chains: SHA-256 with a tenth of the
+    /// default work per entry.
+    pub fn fast() -> Self {
+        PoHConfigBuilder::new(Sha256::new()).tick_interval(DEFAULT_TICK_INTERVAL / 10).config
+    }
+}
+
+impl PoHConfig<Sha512> {
+    /// A profile for long-lived chains: full 64-byte SHA-512 hashes, ten times the
+    /// default work per entry, and previous hashes stored in every entry.
+    pub fn high_security() -> Self {
+        PoHConfigBuilder::new(Sha512::new())
+            .tick_interval(DEFAULT_TICK_INTERVAL * 10)
+            .store_prev_links(true)
+            .config
+    }
+}
+
+#[test]
+fn builder_applies_defaults_and_validates() {
+    let config = PoHConfig::builder(Sha512::new()).build().unwrap();
+    assert_eq!(config.output_size, 64);
+    assert_eq!(config.tick_interval, DEFAULT_TICK_INTERVAL);
+    assert_eq!(config.max_entries, None);
+    assert!(config.allow_data_entries && config.allow_empty_entries);
+    assert_eq!(config.max_data_len, None);
+    assert!(!config.store_prev_links);
+    assert_eq!(config.data_mix, DataMixMode::Suffix);
+    assert_eq!(config.endianness, Endianness::Little);
+    #[cfg(feature = "compression")]
+    assert!(!config.compress_data);
+    assert_eq!(config.window, None);
+    assert_eq!(config.min_interval_between_data, 0);
+    assert!(!config.stretch_seed);
+    assert_eq!(config.tick_entry_type, TickEntryType::Data);
+
+    let config = PoHConfig::builder(Sha256::new()).allow_empty_entries(false).build().unwrap();
+    assert!(config.allow_data_entries && !config.allow_empty_entries);
+
+    let result = PoHConfig::builder(Sha256::new()).output_size(0).build();
+    assert!(matches!(result, Err(PoHError::InvalidConfig(_))));
+    let result = PoHConfig::builder(Sha256::new()).output_size(33).build();
+    assert!(matches!(result, Err(PoHError::OutputSizeMismatch { configured: 33, hasher: 32 })));
+}
+
+#[test]
+fn presets_are_valid() {
+    let config = PoHConfig::default();
+    assert!(config.validate().is_ok());
+    assert_eq!((config.output_size, config.tick_interval, config.max_entries), (32, DEFAULT_TICK_INTERVAL, None));
+    assert!(config.allow_data_entries && config.allow_empty_entries);
+    assert_eq!(config.tick_entry_type, TickEntryType::Data);
+    assert!(PoHConfig::fast().validate().is_ok());
+    assert!(PoHConfig::fast().tick_interval < PoHConfig::default().tick_interval);
+
+    let config = PoHConfig::high_security();
+    assert!(config.validate().is_ok());
+    assert_eq!(config.hash_len(), 64);
+    assert!(config.store_prev_links);
+}
@@ -0,0 +1,78 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Calibration of `tick_interval` against wall-clock time.
+
+use std::time::{Duration, Instant};
+
+use digest::Digest;
+use sha2::Sha256;
+
+use crate::errors::PoHError;
+use crate::{InitialSeed, PoHConfig, PoHUsage};
+
+/// Longest time spent benchmarking; longer durations are extrapolated.
+const MAX_SAMPLE: Duration = Duration::from_millis(100);
+/// Hash rounds run between two clock reads.
+const BATCH: u64 = 256;
+
+/// Estimates how many sequential hash rounds of the configured hasher fit in
+/// `duration` on this machine.
+///
+/// The hasher is benchmarked for `duration` or 100ms, whichever is shorter, and
+/// the measured rate is scaled to `duration`. Use the result as `tick_interval`
+/// to produce roughly one entry per `duration`.
+pub fn calibrate<D: Digest + Clone>(config: &PoHConfig<D>, duration: Duration) -> u64 {
+    if duration.is_zero() {
+        return 0;
+    }
+    let sample = duration.min(MAX_SAMPLE);
+    let mut output = vec![0u8; <D as Digest>::output_size()];
+    let mut rounds: u64 = 0;
+    let start = Instant::now();
+    while start.elapsed() < sample {
+        for _ in 0..BATCH {
+            let mut hasher = config.hasher.clone();
+            hasher.update(&output);
+            output = hasher.finalize().to_vec();
+        }
+        rounds += BATCH;
+    }
+    let elapsed = start.elapsed().as_nanos().max(1);
+    (u128::from(rounds) * duration.as_nanos() / elapsed).min(u128::from(u64::MAX)) as u64
+}
+
+impl PoHUsage<Sha256> {
+    /// Creates a SHA-256 chain whose `tick_interval` is calibrated to produce
+    /// roughly one entry every `target_entry_ms` milliseconds on this machine.
+    ///
+    /// Apart from `tick_interval` and `max_entries` the configuration is the
+    /// default one; the measured interval can be read back from `get_config`.
+    /// Fails as `new` does, e.g. when `target_entry_ms` is too short for a single
+    /// hash round.
+    pub fn auto(seed: InitialSeed, target_entry_ms: u64, max_entries: Option<usize>) -> Result<Self, PoHError> {
+        let mut config = PoHConfig::default();
+        config.tick_interval = calibrate(&config, Duration::from_millis(target_entry_ms));
+        config.max_entries = max_entries;
+        PoHUsage::new(config, seed, None, vec![])
+    }
+}
+
+#[test]
+fn calibrate_scales_with_duration() {
+    use crate::TickEntryType;
+
+    let config = PoHConfig::new(sha2::Sha256::new(), 32, 1, None, true, true, TickEntryType::Data);
+    assert_eq!(calibrate(&config, Duration::ZERO), 0);
+    let per_ms = calibrate(&config, Duration::from_millis(1));
+    assert!(per_ms > 0);
+    assert!(calibrate(&config, Duration::from_secs(1)) > per_ms);
+}
+
+#[test]
+fn auto_calibrates_the_tick_interval() {
+    let poh = PoHUsage::auto(InitialSeed([72; 64]), 1, Some(3)).unwrap();
+    assert!(poh.get_config().tick_interval > 0);
+    assert_eq!(poh.get_config().max_entries, Some(3));
+    assert_eq!(poh.len(), 1);
+    assert_eq!(PoHUsage::auto(InitialSeed([72; 64]), 0, None).err(), Some(PoHError::InvalidConfig("tick_interval must be greater than zero")));
+}
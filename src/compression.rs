@@ -0,0 +1,108 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! zstd compression of appended data.
+//!
+//! With `compress_data` enabled, appended data is stored compressed along with
+//! its original length. Hashes are always computed over the original bytes, so a
+//! chain verifies the same whether or not its data is compressed. The binary
+//! encoding stores the original bytes, and `from_bytes` compresses them again
+//! when the configuration asks for it.
+
+use alloc::borrow::Cow;
+
+use crate::AppendedData;
+
+/// zstd compression level; 0 selects the zstd default.
+const COMPRESSION_LEVEL: i32 = 0;
+
+impl AppendedData {
+    /// Same data, stored compressed. Data that is already compressed, or that
+    /// zstd fails to compress, is returned unchanged.
+    #[must_use]
+    pub fn compressed(self) -> Self {
+        if self.original_len.is_some() {
+            return self;
+        }
+        match zstd::encode_all(self.data.as_slice(), COMPRESSION_LEVEL) {
+            Ok(compressed) => Self { original_len: Some(self.data.len()), data: compressed, ..self },
+            Err(_) => self,
+        }
+    }
+
+    /// Whether the data is stored compressed.
+    #[must_use]
+    pub fn is_compressed(&self) -> bool {
+        self.original_len.is_some()
+    }
+
+    /// Size of the stored, possibly compressed, bytes.
+    #[must_use]
+    pub fn stored_len(&self) -> usize {
+        self.data.len()
+    }
+}
+
+/// Original bytes of compressed `data`. Bytes that do not decompress to the
+/// recorded length, e.g. from a tampered serialized entry, are returned as
+/// stored, so the entry fails verification. Decompression stops at the recorded
+/// length, so a tampered entry cannot make it allocate more than that.
+pub(crate) fn decompress(data: &[u8], original_len: usize) -> Cow<'_, [u8]> {
+    match zstd::bulk::decompress(data, original_len) {
+        Ok(original) if original.len() == original_len => Cow::Owned(original),
+        _ => Cow::Borrowed(data),
+    }
+}
+
+/// Compresses every entry's data when `compress_data` is enabled.
+pub(crate) fn store(compress_data: bool, data: Option<AppendedData>) -> Option<AppendedData> {
+    if compress_data {
+        data.map(AppendedData::compressed)
+    } else {
+        data
+    }
+}
+
+#[test]
+fn compressed_data_hashes_like_the_original() {
+    use digest::Digest;
+
+    use crate::{InitialSeed, PoHConfig, PoHUsage};
+
+    let payload: alloc::vec::Vec<u8> = br#"{"event":"transfer","amount":1}"#.repeat(20);
+    let build = |compress_data| {
+        let config = PoHConfig::builder(sha2::Sha256::new()).tick_interval(10).max_entries(Some(4)).compress_data(compress_data).build().unwrap();
+        let mut poh = PoHUsage::new(config, InitialSeed([59; 64]), Some(payload.clone()), vec![]).unwrap();
+        poh.append_data_entry(payload.clone()).unwrap();
+        poh.resume(1).unwrap();
+        poh
+    };
+    let plain = build(false);
+    let compressed = build(true);
+
+    let data = compressed.state[1].appended_data.as_ref().unwrap();
+    assert!(data.is_compressed());
+    assert!(data.stored_len() < payload.len());
+    assert_eq!(data.len(), payload.len());
+    assert_eq!(&*data.get_data(), payload.as_slice());
+    assert!(compressed.genesis().appended_data.as_ref().unwrap().is_compressed());
+    assert_eq!(compressed.last().unwrap().hash, plain.last().unwrap().hash);
+    assert_eq!(compressed.verify(), Ok(()));
+
+    let bytes = compressed.to_bytes().unwrap();
+    let restored = PoHUsage::from_bytes(&bytes, compressed.config.clone()).unwrap();
+    assert_eq!(restored.state, compressed.state);
+    assert_eq!(PoHUsage::from_bytes(&bytes, plain.config.clone()).unwrap().state, plain.state);
+
+    let mut tampered = compressed;
+    tampered.state[1].appended_data.as_mut().unwrap().data[0] ^= 1;
+    assert!(tampered.verify().is_err());
+}
+
+#[test]
+fn decompression_stops_at_the_recorded_length() {
+    let bomb = AppendedData::new(alloc::vec![0; 1 << 20]).compressed();
+    assert!(bomb.stored_len() < 1024);
+    let claimed = AppendedData { original_len: Some(16), ..bomb.clone() };
+    assert_eq!(&*claimed.get_data(), bomb.data.as_slice());
+    assert_eq!(bomb.get_data().len(), 1 << 20);
+}
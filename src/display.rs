@@ -0,0 +1,172 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Human readable formatting for PoH types.
+//!
+//! Values are rendered as hex. The default form shortens long values to their
+//! first `SHORT_BYTES` bytes followed by an ellipsis, the alternate form (`{:#}`)
+//! prints the full hex.
+
+use core::fmt;
+
+use digest::Digest;
+
+use crate::summary::ChainSummary;
+use crate::{EntryHash, EventHash, InitialSeed, PoHEntry, PoHUsage};
+
+/// Number of leading bytes shown by the default (short) form.
+const SHORT_BYTES: usize = 8;
+
+fn write_hex(f: &mut fmt::Formatter<'_>, bytes: &[u8]) -> fmt::Result {
+    if f.alternate() || bytes.len() <= SHORT_BYTES {
+        write!(f, "{}", hex::encode(bytes))
+    } else {
+        write!(f, "{}…", hex::encode(&bytes[..SHORT_BYTES]))
+    }
+}
+
+impl fmt::Display for InitialSeed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_hex(f, &self.0)
+    }
+}
+
+impl fmt::Display for EventHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_hex(f, &self.hash)
+    }
+}
+
+impl fmt::Display for EntryHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_hex(f, self)
+    }
+}
+
+/// Renders the entry hash, followed by `+data` when the entry carries appended data.
+impl fmt::Display for PoHEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_hex(f, &self.hash)?;
+        if self.appended_data.is_some() {
+            write!(f, " +data")?;
+        }
+        Ok(())
+    }
+}
+
+/// Renders a one-line overview, e.g.
+/// `4 entries, 31 hashes, 2 with data, genesis 1a2b…, tip 3c4d…, extensions [MerkleTree], time 150..200`.
+/// The extensions and time span are omitted when there are none.
+impl fmt::Display for ChainSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} entries, {} hashes, {} with data, genesis ", self.entries, self.total_work, self.data_entries)?;
+        write_hex(f, &self.genesis_hash)?;
+        write!(f, ", tip ")?;
+        write_hex(f, &self.tip_hash)?;
+        if !self.extensions.is_empty() {
+            write!(f, ", extensions {:?}", self.extensions)?;
+        }
+        if let Some((first, last)) = self.time_span {
+            write!(f, ", time {}..{}", first, last)?;
+        }
+        Ok(())
+    }
+}
+
+/// Hex bytes shortened like `write_hex`, for use as a `Debug` field.
+struct Hex<'a>(&'a [u8]);
+
+impl fmt::Debug for Hex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_hex(f, self.0)
+    }
+}
+
+/// The default form (`{:?}`) summarizes the chain, showing the shortened genesis
+/// and tip hashes instead of the entries, so it stays small on long chains. The
+/// alternate form (`{:#?}`) prints every field, including all entries.
+impl<D: Digest + Clone + fmt::Debug> fmt::Debug for PoHUsage<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            let mut debug = f.debug_struct("PoHUsage");
+            debug
+                .field("id", &self.id)
+                .field("config", &self.config)
+                .field("state", &self.state)
+                .field("extensions", &self.extensions)
+                .field("milestones", &self.milestones);
+            #[cfg(feature = "std")]
+            debug.field("last_run", &self.last_run);
+            debug.field("pruned_commitment", &self.pruned_commitment);
+            #[cfg(feature = "ed25519")]
+            debug.field("signatures", &self.signatures);
+            return debug.finish();
+        }
+        let genesis = self.state.first().map_or(&[][..], |entry| entry.hash.as_slice());
+        let tip = self.state.last().map_or(&[][..], |entry| entry.hash.as_slice());
+        f.debug_struct("PoHUsage")
+            .field("id", &self.id)
+            .field("output_size", &self.config.output_size)
+            .field("tick_interval", &self.config.tick_interval)
+            .field("max_entries", &self.config.max_entries)
+            .field("tick_entry_type", &self.config.tick_entry_type)
+            .field("entries", &self.state.len())
+            .field("genesis", &Hex(genesis))
+            .field("tip", &Hex(tip))
+            .field("extensions", &self.extensions)
+            .finish_non_exhaustive()
+    }
+}
+
+#[test]
+fn display_renders_hex() {
+    let seed = InitialSeed([0xab; 64]);
+    assert_eq!(seed.to_string(), format!("{}…", "ab".repeat(SHORT_BYTES)));
+    assert_eq!(format!("{:#}", seed), "ab".repeat(64));
+
+    let event = EventHash::from_data(b"event");
+    assert_eq!(format!("{:#}", event), hex::encode(event.hash));
+
+    let mut entry = PoHEntry { hash: vec![1, 2, 3].into(), ..Default::default() };
+    assert_eq!(entry.hash.to_string(), "010203");
+    assert_eq!(entry.to_string(), "010203");
+    entry.appended_data = Some(crate::AppendedData::new(vec![7]));
+    assert_eq!(entry.to_string(), "010203 +data");
+    assert_eq!(format!("{:#}", entry), "010203 +data");
+}
+
+#[test]
+fn display_renders_summaries() {
+    let mut summary = ChainSummary {
+        entries: 4,
+        total_work: 31,
+        genesis_hash: vec![0x1a; 32],
+        tip_hash: vec![0x3c, 0x4d],
+        data_entries: 2,
+        extensions: vec![],
+        time_span: None,
+    };
+    assert_eq!(summary.to_string(), format!("4 entries, 31 hashes, 2 with data, genesis {}…, tip 3c4d", "1a".repeat(SHORT_BYTES)));
+    summary.extensions = vec![crate::extensions::Extensions::MerkleTree];
+    summary.time_span = Some((150, 200));
+    assert!(format!("{:#}", summary).ends_with(&format!("{}, tip 3c4d, extensions [MerkleTree], time 150..200", "1a".repeat(32))));
+}
+
+#[test]
+fn debug_summarizes_chains() {
+    use crate::{PoHConfig, TickEntryType};
+
+    let config = PoHConfig::new(sha2::Sha256::new(), 32, 5, Some(50), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed([57; 64]), None, vec![]).unwrap();
+    poh.init().unwrap();
+
+    let short = format!("{:?}", poh);
+    assert!(short.starts_with("PoHUsage { id: "));
+    assert!(short.contains("entries: 51"));
+    assert!(short.contains(&format!("tip: {}…", hex::encode(&poh.last().unwrap().hash[..SHORT_BYTES]))));
+    assert!(short.ends_with(", .. }"));
+    assert!(!short.contains("state"));
+
+    let verbose = format!("{:#?}", poh);
+    assert!(verbose.contains("state: ["));
+    assert!(verbose.len() > 10 * short.len());
+}
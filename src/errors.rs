@@ -0,0 +1,244 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Error types for the Proof of History (PoH) process.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::extensions::Extensions;
+use crate::TickEntryType;
+
+/// Errors reported while building or extending a PoH chain.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PoHError {
+    /// Data was supplied but the configuration does not allow data entries.
+    DataNotAllowed,
+    /// The appended data is `len` bytes long, more than the configured `max` bytes.
+    DataTooLarge { len: usize, max: usize },
+    /// No data was supplied but the configuration does not allow empty entries.
+    EmptyNotAllowed,
+    /// The chain already holds `max` entries after the genesis entry.
+    MaxEntriesReached { max: usize },
+    /// The next entry would need an id beyond `u64::MAX`.
+    IdsExhausted,
+    /// The entry to extend is not the tip of the chain.
+    NotTip,
+    /// The configured `output_size` is larger than the digest the hasher produces.
+    OutputSizeMismatch { configured: usize, hasher: usize },
+    /// The appended data conflicts with the configured `tick_entry_type`.
+    EntryTypeMismatch(TickEntryType),
+    /// The configuration violates an invariant of the PoH process.
+    InvalidConfig(&'static str),
+    /// Encoded bytes could not be decoded into a chain.
+    Decode(&'static str),
+    /// The chain could not be encoded, e.g. because a field is too long for its
+    /// length prefix.
+    Encode(&'static str),
+    /// Encoded bytes do not match their checksum, e.g. after truncation or a bit flip.
+    Corrupt,
+    /// The operation requires an extension that is not active.
+    ExtensionNotActive(Extensions),
+    /// The two extensions cannot be active together.
+    ConflictingExtensions(Extensions, Extensions),
+    /// A milestone with this name already exists.
+    DuplicateMilestone(String),
+    /// The operation would never finish because `max_entries` is `None`.
+    Unbounded,
+    /// The configured work, `tick_interval` hash rounds for each of `max_entries`
+    /// entries, exceeds `u64::MAX` rounds.
+    TooLarge,
+    /// A seed was read from `len` bytes instead of 64.
+    InvalidSeedLength { len: usize },
+    /// The hash of the entry at `index` is `len` bytes long instead of `expected`.
+    HashLengthMismatch { index: usize, len: usize, expected: usize },
+    /// A hash is `len` bytes long instead of the `expected` length.
+    InvalidHashLength { len: usize, expected: usize },
+    /// An instance with the same seed, initial data and configuration already exists.
+    SeedReused,
+    /// `index` does not refer to one of the `len` entries of the chain.
+    IndexOutOfRange { index: usize, len: usize },
+    /// An adopted chain failed verification.
+    Verify(VerifyError),
+}
+
+impl fmt::Display for PoHError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PoHError::DataNotAllowed => write!(f, "data entries are not allowed by the configuration"),
+            PoHError::DataTooLarge { len, max } => {
+                write!(f, "appended data of {} bytes exceeds the limit of {} bytes", len, max)
+            }
+            PoHError::EmptyNotAllowed => write!(f, "empty entries are not allowed by the configuration"),
+            PoHError::MaxEntriesReached { max } => write!(f, "the chain already holds the maximum of {} entries", max),
+            PoHError::IdsExhausted => write!(f, "the chain has used up every entry id"),
+            PoHError::NotTip => write!(f, "the entry to extend is not the tip of the chain"),
+            PoHError::OutputSizeMismatch { configured, hasher } => write!(
+                f,
+                "output size of {} bytes exceeds the {} byte digest of the hasher",
+                configured, hasher
+            ),
+            PoHError::EntryTypeMismatch(entry_type) => {
+                write!(f, "appended data conflicts with the {:?} entry type", entry_type)
+            }
+            PoHError::InvalidConfig(reason) => write!(f, "invalid configuration: {}", reason),
+            PoHError::Decode(reason) => write!(f, "failed to decode chain: {}", reason),
+            PoHError::Encode(reason) => write!(f, "failed to encode chain: {}", reason),
+            PoHError::Corrupt => write!(f, "encoded chain does not match its checksum"),
+            PoHError::ExtensionNotActive(extension) => write!(f, "the {:?} extension is not active", extension),
+            PoHError::ConflictingExtensions(a, b) => write!(f, "the {:?} and {:?} extensions conflict", a, b),
+            PoHError::DuplicateMilestone(name) => write!(f, "a milestone named {:?} already exists", name),
+            PoHError::Unbounded => write!(f, "the chain has no max_entries limit, generate it lazily instead"),
+            PoHError::TooLarge => write!(f, "the configured work exceeds {} hash rounds", u64::MAX),
+            PoHError::InvalidSeedLength { len } => write!(f, "a seed must be 64 bytes long, got {}", len),
+            PoHError::HashLengthMismatch { index, len, expected } => {
+                write!(f, "hash of entry {} is {} bytes long, expected {}", index, len, expected)
+            }
+            PoHError::InvalidHashLength { len, expected } => write!(f, "hash is {} bytes long, expected {}", len, expected),
+            PoHError::SeedReused => write!(f, "the seed was already used with the same initial data and configuration"),
+            PoHError::IndexOutOfRange { index, len } => {
+                write!(f, "index {} is out of range for a chain of {} entries", index, len)
+            }
+            PoHError::Verify(err) => write!(f, "chain failed verification: {}", err),
+        }
+    }
+}
+
+impl core::error::Error for PoHError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            PoHError::Verify(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<VerifyError> for PoHError {
+    fn from(err: VerifyError) -> Self {
+        PoHError::Verify(err)
+    }
+}
+
+/// Malformed hex is reported as `PoHError::Decode`.
+impl From<hex::FromHexError> for PoHError {
+    fn from(err: hex::FromHexError) -> Self {
+        PoHError::Decode(match err {
+            hex::FromHexError::InvalidHexCharacter { .. } => "invalid hex character",
+            hex::FromHexError::OddLength => "odd number of hex digits",
+            hex::FromHexError::InvalidStringLength => "hex string has the wrong length",
+        })
+    }
+}
+
+/// Errors about the bytes being read, i.e. `Decode`, `Corrupt` and `Verify`,
+/// become `io::ErrorKind::InvalidData`; everything else is `InvalidInput`.
+#[cfg(feature = "std")]
+impl From<PoHError> for std::io::Error {
+    fn from(err: PoHError) -> Self {
+        let kind = match err {
+            PoHError::Decode(_) | PoHError::Corrupt | PoHError::Verify(_) => std::io::ErrorKind::InvalidData,
+            _ => std::io::ErrorKind::InvalidInput,
+        };
+        std::io::Error::new(kind, err)
+    }
+}
+
+/// Errors reported when replaying a PoH chain.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum VerifyError {
+    /// The chain has no entries to verify.
+    EmptyChain,
+    /// The entry at `index` does not match the hash recomputed from its predecessor.
+    /// `expected` is the recomputed hash, `actual` is the hash stored in the entry.
+    HashMismatch {
+        index: usize,
+        expected: Vec<u8>,
+        actual: Vec<u8>,
+    },
+    /// The hash of the entry at `index` is `len` bytes long instead of `expected`.
+    HashLengthMismatch { index: usize, len: usize, expected: usize },
+    /// The entry at `index` violates the configuration or the layout of a chain.
+    Malformed { index: usize, reason: &'static str },
+    /// The previous hash recorded in the entry at `index` is not the hash of its predecessor.
+    PrevLinkMismatch { index: usize },
+    /// The entry at `index` does not record the hash of its predecessor.
+    MissingPrevLink { index: usize },
+    /// `start..end` is not a non-empty range of the `len` entries of the chain.
+    InvalidRange { start: usize, end: usize, len: usize },
+    /// Verification was cancelled after `done` entries were checked.
+    Cancelled { done: usize },
+    /// The data entry at `index` ends `rounds` hash rounds after the previous data
+    /// entry, fewer than the configured `min_interval_between_data`.
+    DataTooClose { index: usize, rounds: u64, min: u64 },
+    /// The pruned entries do not reproduce the stored pruning commitment.
+    CommitmentMismatch,
+    /// No pruned entries were given, or the chain holds no pruning commitment to
+    /// check them against.
+    NothingPruned,
+    /// The timestamp of the entry at `index` is earlier than the `previous` one.
+    TimestampRegression { index: usize, previous: u64, timestamp: u64 },
+    /// The timestamp of the entry at `index` is more than `max_skew` seconds after
+    /// the `previous` one.
+    TimestampSkew { index: usize, previous: u64, timestamp: u64, max_skew: u64 },
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyError::EmptyChain => write!(f, "the PoH chain has no entries"),
+            VerifyError::HashMismatch { index, expected, actual } => write!(
+                f,
+                "hash mismatch at entry {}: expected {}, found {}",
+                index,
+                hex::encode(expected),
+                hex::encode(actual)
+            ),
+            VerifyError::HashLengthMismatch { index, len, expected } => {
+                write!(f, "hash of entry {} is {} bytes long, expected {}", index, len, expected)
+            }
+            VerifyError::Malformed { index, reason } => write!(f, "entry {} is malformed: {}", index, reason),
+            VerifyError::PrevLinkMismatch { index } => {
+                write!(f, "entry {} records a previous hash that does not match its predecessor", index)
+            }
+            VerifyError::MissingPrevLink { index } => write!(f, "entry {} does not record its previous hash", index),
+            VerifyError::InvalidRange { start, end, len } => {
+                write!(f, "range {}..{} is not within a chain of {} entries", start, end, len)
+            }
+            VerifyError::Cancelled { done } => write!(f, "verification was cancelled after {} entries", done),
+            VerifyError::DataTooClose { index, rounds, min } => write!(
+                f,
+                "data entry {} ends {} hash rounds after the previous data entry, fewer than {}",
+                index, rounds, min
+            ),
+            VerifyError::CommitmentMismatch => write!(f, "the pruned entries do not match the pruning commitment"),
+            VerifyError::NothingPruned => write!(f, "there are no pruned entries to check against a pruning commitment"),
+            VerifyError::TimestampRegression { index, previous, timestamp } => write!(
+                f,
+                "timestamp of entry {} goes backwards: {} is earlier than {}",
+                index, timestamp, previous
+            ),
+            VerifyError::TimestampSkew { index, previous, timestamp, max_skew } => write!(
+                f,
+                "timestamp of entry {} jumps ahead: {} is more than {} seconds after {}",
+                index, timestamp, max_skew, previous
+            ),
+        }
+    }
+}
+
+impl core::error::Error for VerifyError {}
+
+#[test]
+fn errors_convert_from_foreign_errors() {
+    assert_eq!(PoHError::from(hex::decode("abc").unwrap_err()), PoHError::Decode("odd number of hex digits"));
+    assert_eq!(PoHError::from(hex::decode("zz").unwrap_err()), PoHError::Decode("invalid hex character"));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn errors_convert_into_io_errors() {
+    assert_eq!(std::io::Error::from(PoHError::Corrupt).kind(), std::io::ErrorKind::InvalidData);
+    let err = std::io::Error::from(PoHError::Unbounded);
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(err.to_string(), PoHError::Unbounded.to_string());
+}
@@ -1,7 +1,17 @@
+use alloc::vec::Vec;
+
+use crate::errors::PoHError;
+
+/// Optional behaviour of a PoH chain.
+///
+/// Each extension is active at most once. `Timestamp` and `TimestampRFC3339`
+/// conflict, since both would stamp every entry, and so do `Breaker`s with
+/// different intervals. `PoHUsage::new` drops duplicates and rejects conflicts.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Extensions {
     TickHasher, // Used for hashing the ticks
-    Breaker, // Used for breaking the ticks
+    Breaker(u64), // Used for breaking the ticks into epochs, with a breaker entry every N entries
     MerkleTree, // Used for creating the merkle tree
     
     // Timestamps
@@ -10,4 +20,64 @@ pub enum Extensions {
 
     // Milestones
     Milestone, // Used for creating milestones in the PoH process
-}
\ No newline at end of file
+}
+
+/// Removes duplicate extensions, keeping the first occurrence, and rejects
+/// extensions that cannot be active together.
+pub(crate) fn normalize(extensions: &mut Vec<Extensions>) -> Result<(), PoHError> {
+    let mut unique: Vec<Extensions> = Vec::with_capacity(extensions.len());
+    for &extension in extensions.iter() {
+        if unique.contains(&extension) {
+            continue;
+        }
+        if let Some(&active) = unique.iter().find(|active| conflicts(**active, extension)) {
+            return Err(PoHError::ConflictingExtensions(active, extension));
+        }
+        unique.push(extension);
+    }
+    *extensions = unique;
+    Ok(())
+}
+
+fn conflicts(a: Extensions, b: Extensions) -> bool {
+    matches!(
+        (a, b),
+        (Extensions::Timestamp, Extensions::TimestampRFC3339)
+            | (Extensions::TimestampRFC3339, Extensions::Timestamp)
+            | (Extensions::Breaker(_), Extensions::Breaker(_))
+    ) && a != b
+}
+
+/// Tag folded into the hash of every breaker entry.
+pub(crate) const BREAKER_TAG: &[u8] = b"poh-yugen/breaker";
+
+/// The breaker interval of the active `Breaker` extension, if any.
+pub(crate) fn break_interval(extensions: &[Extensions]) -> Option<u64> {
+    extensions.iter().find_map(|extension| match extension {
+        Extensions::Breaker(interval) if *interval > 0 => Some(*interval),
+        _ => None,
+    })
+}
+
+/// Whether the entry with `id` is a breaker entry under the active `Breaker` extension.
+pub(crate) fn breaker_due(extensions: &[Extensions], id: u64) -> bool {
+    break_interval(extensions).is_some_and(|interval| id.is_multiple_of(interval))
+}
+
+#[test]
+fn normalize_dedups_and_rejects_conflicts() {
+    let mut extensions = vec![Extensions::MerkleTree, Extensions::Breaker(4), Extensions::MerkleTree, Extensions::Breaker(4)];
+    normalize(&mut extensions).unwrap();
+    assert_eq!(extensions, vec![Extensions::MerkleTree, Extensions::Breaker(4)]);
+
+    let mut extensions = vec![Extensions::Timestamp, Extensions::Milestone, Extensions::TimestampRFC3339];
+    assert_eq!(
+        normalize(&mut extensions),
+        Err(PoHError::ConflictingExtensions(Extensions::Timestamp, Extensions::TimestampRFC3339))
+    );
+    let mut extensions = vec![Extensions::Breaker(4), Extensions::Breaker(5)];
+    assert_eq!(
+        normalize(&mut extensions),
+        Err(PoHError::ConflictingExtensions(Extensions::Breaker(4), Extensions::Breaker(5)))
+    );
+}
@@ -0,0 +1,91 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tick generation on a `tokio` runtime.
+//!
+//! Hashing is CPU bound, so generation hands control back to the executor
+//! between ticks or batches of ticks. Other tasks on the runtime stay
+//! responsive while a chain is being produced.
+
+use digest::Digest;
+use tokio::sync::mpsc;
+use tokio::task::{self, JoinHandle};
+
+use crate::errors::PoHError;
+use crate::{InitialSeed, PoHConfig, PoHEntry, PoHUsage};
+
+/// Entries generated by `spawn_generator` between two yields to the executor.
+pub const GENERATOR_BATCH: usize = 64;
+
+impl<D: Digest + Clone> PoHUsage<D> {
+    /// Spawns a task that generates a chain from `seed` and sends every entry,
+    /// starting with the genesis entry, over `tx`.
+    ///
+    /// Only the tip is kept in memory. The task yields to the executor after every
+    /// `GENERATOR_BATCH` entries, and stops once `max_entries` is reached or the
+    /// receiver is dropped; with `max_entries: None` it runs until then. Must be
+    /// called from within a `tokio` runtime. Fails without spawning when the
+    /// configuration is invalid.
+    pub fn spawn_generator(config: PoHConfig<D>, seed: InitialSeed, tx: mpsc::Sender<PoHEntry>) -> Result<JoinHandle<()>, PoHError>
+    where
+        D: Send + Sync + 'static,
+    {
+        let poh = PoHUsage::new(config, seed, None, vec![])?;
+        poh.config.check_output_size()?;
+        Ok(task::spawn(async move {
+            if tx.send(poh.genesis().clone()).await.is_err() {
+                return;
+            }
+            for (index, entry) in poh.iter_ticks().enumerate() {
+                if tx.send(entry).await.is_err() {
+                    return;
+                }
+                if (index + 1) % GENERATOR_BATCH == 0 {
+                    task::yield_now().await;
+                }
+            }
+        }))
+    }
+
+    /// Yields to the executor, then appends one empty tick and returns it.
+    ///
+    /// Fails as `resume` does, e.g. when `max_entries` has been reached.
+    pub async fn next_tick(&mut self) -> Result<PoHEntry, PoHError> {
+        task::yield_now().await;
+        self.resume(1)?;
+        Ok(self.last().expect("PoH state always holds the genesis entry").clone())
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn generator_streams_a_verifiable_chain() {
+    use crate::TickEntryType;
+
+    let config = PoHConfig::new(sha2::Sha256::new(), 32, 10, Some(150), true, true, TickEntryType::Data);
+    let (tx, mut rx) = mpsc::channel(8);
+    let handle = PoHUsage::spawn_generator(config.clone(), InitialSeed([38; 64]), tx).unwrap();
+
+    let mut entries = Vec::new();
+    while let Some(entry) = rx.recv().await {
+        entries.push(entry);
+    }
+    handle.await.unwrap();
+    assert_eq!(entries.len(), 151);
+
+    let mut poh = PoHUsage::new(config.clone(), InitialSeed([38; 64]), None, vec![]).unwrap();
+    assert_eq!(poh.next_tick().await.unwrap(), entries[1]);
+    assert!(PoHUsage::from_state(config, entries, vec![]).is_ok());
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn generator_stops_when_the_receiver_is_dropped() {
+    use crate::TickEntryType;
+
+    let config = PoHConfig::new(sha2::Sha256::new(), 32, 10, None, true, true, TickEntryType::Data);
+    let (tx, mut rx) = mpsc::channel(1);
+    let handle = PoHUsage::spawn_generator(config, InitialSeed([39; 64]), tx).unwrap();
+    assert!(rx.recv().await.is_some());
+    drop(rx);
+    handle.await.unwrap();
+}
@@ -0,0 +1,115 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! The hash stored in every PoH entry.
+//!
+//! `EntryHash` owns the hash bytes. `EntryHash::new` checks them against the
+//! length the configuration produces, so hashes of the wrong length are caught
+//! where they enter a chain: the binary format, snapshots, JSON lines exports and
+//! test vectors all decode hashes through it. Deserialized entries carry no
+//! configuration, so `PoHUsage::verify`, and thus `from_state`, checks their
+//! length instead. The bytes can be read and modified in place through `Deref`,
+//! but never resized.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::{Deref, DerefMut};
+
+use crate::errors::PoHError;
+
+/// Hash of a PoH entry.
+///
+/// The default value is empty and only serves as a placeholder for entries that
+/// have not been hashed yet.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EntryHash(Vec<u8>);
+
+impl EntryHash {
+    /// Wraps `bytes`, failing with `PoHError::InvalidHashLength` unless they are
+    /// `expected_len` bytes long, e.g. `PoHConfig::hash_len`.
+    pub fn new(bytes: Vec<u8>, expected_len: usize) -> Result<Self, PoHError> {
+        if bytes.len() != expected_len {
+            return Err(PoHError::InvalidHashLength { len: bytes.len(), expected: expected_len });
+        }
+        Ok(EntryHash(bytes))
+    }
+    /// Wraps a hash the crate computed under a configuration, which has the
+    /// configured length by construction.
+    pub(crate) fn computed(bytes: Vec<u8>) -> Self {
+        EntryHash(bytes)
+    }
+    #[must_use]
+    pub fn as_slice(&self) -> &[u8] {
+        &self.0
+    }
+    #[must_use]
+    pub fn into_vec(self) -> Vec<u8> {
+        self.0
+    }
+    #[must_use]
+    pub fn to_hex(&self) -> String {
+        hex::encode(&self.0)
+    }
+}
+
+/// Deprecated migration path for code that still builds hashes as `Vec<u8>`:
+/// wraps `bytes` without checking their length. Use `EntryHash::new` instead.
+/// The crate itself does not rely on it, and the impl remains only so that
+/// existing callers keep compiling.
+impl From<Vec<u8>> for EntryHash {
+    fn from(bytes: Vec<u8>) -> Self {
+        EntryHash(bytes)
+    }
+}
+
+impl From<EntryHash> for Vec<u8> {
+    fn from(hash: EntryHash) -> Self {
+        hash.0
+    }
+}
+
+impl Deref for EntryHash {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl DerefMut for EntryHash {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+}
+
+impl AsRef<[u8]> for EntryHash {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl PartialEq<[u8]> for EntryHash {
+    fn eq(&self, other: &[u8]) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<Vec<u8>> for EntryHash {
+    fn eq(&self, other: &Vec<u8>) -> bool {
+        &self.0 == other
+    }
+}
+
+impl PartialEq<EntryHash> for Vec<u8> {
+    fn eq(&self, other: &EntryHash) -> bool {
+        self == &other.0
+    }
+}
+
+#[test]
+fn entry_hashes_check_their_length() {
+    let hash = EntryHash::new(vec![1, 2, 3], 3).unwrap();
+    assert_eq!(hash, vec![1, 2, 3]);
+    assert_eq!(hash.to_hex(), "010203");
+    assert_eq!(EntryHash::new(vec![1, 2], 3), Err(PoHError::InvalidHashLength { len: 2, expected: 3 }));
+    assert_eq!(Vec::from(EntryHash::from(vec![4])), vec![4]);
+}
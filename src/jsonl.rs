@@ -0,0 +1,160 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! JSON lines export of PoH chains, for log processing tools such as `jq`.
+//!
+//! Every entry becomes one JSON object on its own line, e.g.
+//!
+//! ```text
+//! {"index":1,"hash":"4a2f…","data":"68656c6c6f","nonce":0}
+//! ```
+//!
+//! Hashes and data are hex-encoded, and data is always written in its original,
+//! uncompressed form. Fields that are unset on an entry are left out. Lines are
+//! written and read one at a time, so exports stream without building one large
+//! document.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+
+use digest::Digest;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::PoHError;
+use crate::{AppendedData, EntryHash, EventHash, PoHEntry, PoHUsage};
+
+/// One line of a JSON lines export.
+#[derive(Serialize, Deserialize)]
+struct Record {
+    index: u64,
+    #[serde(with = "crate::serde_hex")]
+    hash: Vec<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "crate::serde_hex::option")]
+    data: Option<Vec<u8>>,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    lane: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timestamp: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timestamp_rfc3339: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    fingerprint: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "crate::serde_hex::option")]
+    prev: Option<Vec<u8>>,
+    #[serde(default, skip_serializing_if = "core::ops::Not::not")]
+    breaker: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    milestones: Vec<String>,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    interval: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    nonce: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    event: Option<EventHash>,
+}
+
+fn is_zero<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+impl<D: Digest + Clone> PoHUsage<D> {
+    /// Writes every entry as one line of JSON, in chain order.
+    pub fn write_jsonl<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for entry in &self.state {
+            serde_json::to_writer(&mut writer, &Record::from(entry))?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()
+    }
+}
+
+/// Reads the entries of an export written by `PoHUsage::write_jsonl`.
+///
+/// Blank lines are skipped. Every hash must be as long as the first one, but the
+/// entries are not verified; pass them to `PoHUsage::from_state` to adopt them as
+/// a chain. Malformed lines are reported as `io::ErrorKind::InvalidData`.
+pub fn read_jsonl<R: Read>(reader: R) -> io::Result<Vec<PoHEntry>> {
+    let mut entries = Vec::new();
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: Record = serde_json::from_str(&line)?;
+        let hash_len = entries.first().map_or(record.hash.len(), |first: &PoHEntry| first.hash.len());
+        entries.push(record.into_entry(hash_len).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?);
+    }
+    Ok(entries)
+}
+
+impl From<&PoHEntry> for Record {
+    fn from(entry: &PoHEntry) -> Self {
+        Record {
+            index: entry.id,
+            hash: entry.hash.to_vec(),
+            data: entry.appended_data.as_ref().map(AppendedData::to_vec),
+            lane: entry.appended_data.as_ref().map_or(0, AppendedData::lane),
+            timestamp: entry.timestamp,
+            timestamp_rfc3339: entry.timestamp_rfc3339.clone(),
+            fingerprint: entry.fingerprint.clone(),
+            prev: entry.prev.clone(),
+            breaker: entry.breaker,
+            milestones: entry.milestones.clone(),
+            interval: entry.interval,
+            nonce: entry.nonce,
+            event: entry.event,
+        }
+    }
+}
+
+impl Record {
+    /// The entry this line describes, failing unless its hash is `hash_len` bytes long.
+    fn into_entry(self, hash_len: usize) -> Result<PoHEntry, PoHError> {
+        Ok(PoHEntry {
+            id: self.index,
+            timestamp: self.timestamp,
+            timestamp_rfc3339: self.timestamp_rfc3339,
+            fingerprint: self.fingerprint,
+            hash: EntryHash::new(self.hash, hash_len)?,
+            prev: self.prev,
+            appended_data: self.data.map(|data| AppendedData::with_lane(self.lane, data)),
+            breaker: self.breaker,
+            milestones: self.milestones,
+            interval: self.interval,
+            nonce: self.nonce,
+            event: self.event,
+        })
+    }
+}
+
+#[test]
+fn jsonl_round_trips_entries() {
+    use crate::extensions::Extensions;
+    use crate::{InitialSeed, PoHConfig, TickEntryType};
+
+    let config = PoHConfig::new(sha2::Sha256::new(), 32, 10, Some(6), true, true, TickEntryType::Data);
+    let extensions = vec![Extensions::Timestamp, Extensions::Milestone];
+    let mut poh = PoHUsage::new(config.clone(), InitialSeed([62; 64]), None, extensions.clone()).unwrap();
+    poh.append_data_entry(b"hello".to_vec()).unwrap();
+    poh.add_milestone("first".into()).unwrap();
+    poh.append_to_lane(3, b"meta".to_vec()).unwrap();
+    poh.resume(2).unwrap();
+
+    let mut out = Vec::new();
+    poh.write_jsonl(&mut out).unwrap();
+    let text = String::from_utf8(out.clone()).unwrap();
+    assert_eq!(text.lines().count(), poh.len());
+    let line: serde_json::Value = serde_json::from_str(text.lines().nth(1).unwrap()).unwrap();
+    assert_eq!(line["index"], 1);
+    assert_eq!(line["hash"], hex::encode(&poh.state[1].hash));
+    assert_eq!(line["data"], hex::encode(b"hello"));
+    assert!(line["timestamp"].is_u64());
+
+    let entries = read_jsonl(out.as_slice()).unwrap();
+    assert_eq!(entries, poh.state);
+    assert!(PoHUsage::from_state(config, entries, extensions).is_ok());
+
+    let short = text.replacen(&hex::encode(&poh.state[2].hash), &hex::encode(&poh.state[2].hash[..31]), 1);
+    assert_eq!(read_jsonl(short.as_bytes()).unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+    let error = read_jsonl(&b"{\"index\":0}\n"[..]).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+}
@@ -6,17 +6,73 @@
 //! of a verifiable and tamper-proof history of events. It is used in various
 //! blockchain systems to provide a secure and efficient way to order transactions
 //! and events in a distributed network.
-//! 
+//!
+//! The crate is `no_std` compatible: disable the default `std` feature to build
+//! against `alloc` only. Without `std` the `timestamps` module is unavailable and
+//! the `Timestamp` and `TimestampRFC3339` extensions leave entries unstamped.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicUsize, Ordering};
 
 // Digestis used for hashing and cryptographic operations.
 // The `digest` crate provides a variety of hashing algorithms and utilities
-use digest::{Digest,Output,OutputSizeUser};
+use digest::Digest;
+#[cfg(test)]
 use sha2::Sha256;
 
 
+pub mod anchor;
+pub mod binary;
+pub mod builder;
+#[cfg(feature = "std")]
+pub mod calibrate;
+#[cfg(feature = "compression")]
+pub mod compression;
+mod display;
+pub mod errors;
 pub mod extensions;
+#[cfg(feature = "async")]
+pub mod generator;
+pub mod hash;
+#[cfg(all(feature = "serde", feature = "std"))]
+pub mod jsonl;
+pub mod merkle;
 pub mod prelude;
+pub mod prune;
+#[cfg(feature = "serde")]
+mod serde_hex;
+#[cfg(feature = "ed25519")]
+pub mod signing;
+pub mod snapshot;
+#[cfg(feature = "std")]
+pub mod stats;
+#[cfg(feature = "std")]
+pub mod stream;
+pub mod summary;
+#[cfg(feature = "test-vectors")]
+pub mod test_vectors;
+#[cfg(feature = "std")]
 pub mod timestamps;
+pub mod verify;
+pub mod xof;
+
+pub use builder::PoHConfigBuilder;
+#[cfg(feature = "std")]
+pub use calibrate::calibrate;
+#[cfg(feature = "std")]
+pub use stats::RunStats;
+pub use anchor::{aggregate_roots, verify_aggregate};
+pub use errors::{PoHError, VerifyError};
+pub use hash::EntryHash;
+pub use merkle::{verify_merkle_proof, verify_mmr_proof, MmrProof};
+pub use summary::ChainSummary;
 
 pub trait Seedable {
     /// Generates a new seed for the Proof of History (PoH) algorithm.
@@ -47,33 +103,205 @@ pub trait EventData<D: Digest> {
 /// the integrity of the PoH output.
 /// The seed should be generated using a cryptographically secure random number generator
 /// to ensure that it is unpredictable and resistant to attacks.
+///
+/// Seeds are deliberately not `Copy`, so every copy of the secret is an explicit
+/// `clone`. With the `zeroize` feature a seed is wiped from memory when dropped;
+/// the state of the hasher it was fed into is not.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
+pub struct InitialSeed(#[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))] pub [u8; 64]);
+
+impl InitialSeed {
+    /// Generates a fresh seed from the operating system's CSPRNG.
+    #[cfg(feature = "rand")]
+    pub fn random() -> Self {
+        InitialSeed(RandomSeed::generate_seed())
+    }
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8; 64] {
+        &self.0
+    }
+    /// Derives a seed from a passphrase with PBKDF2-HMAC-SHA512 and
+    /// `PASSPHRASE_ROUNDS` iterations.
+    ///
+    /// The same passphrase and salt always give the same seed, which makes chains
+    /// reproducible across machines.
+    #[cfg(feature = "pbkdf2")]
+    pub fn from_passphrase(pass: &str, salt: &[u8]) -> Self {
+        Self::from_passphrase_with_rounds(pass, salt, PASSPHRASE_ROUNDS)
+    }
+    /// Same as `from_passphrase` with an explicit PBKDF2 iteration count.
+    #[cfg(feature = "pbkdf2")]
+    pub fn from_passphrase_with_rounds(pass: &str, salt: &[u8], rounds: u32) -> Self {
+        let mut seed = [0u8; 64];
+        pbkdf2::pbkdf2_hmac::<sha2::Sha512>(pass.as_bytes(), salt, rounds, &mut seed);
+        InitialSeed(seed)
+    }
+    /// Derives a seed by hashing this one `rounds` times with SHA-512.
+    ///
+    /// Stretching adds no entropy: a weak seed stays weak. It makes every guess
+    /// `rounds` hashes more expensive, slowing down brute force and precomputation
+    /// of genesis entries from guessable seeds. Used for the genesis entry when
+    /// `PoHConfig::stretch_seed` is set.
+    #[must_use]
+    pub fn stretched(&self, rounds: u32) -> InitialSeed {
+        let mut seed = self.clone();
+        for _ in 0..rounds {
+            let digest = sha2::Sha512::new_with_prefix(SEED_STRETCH_TAG).chain_update(seed.0).finalize();
+            seed.0.copy_from_slice(&digest);
+        }
+        seed
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for InitialSeed {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for InitialSeed {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for InitialSeed {}
+
+/// SHA-512 rounds a seed is stretched by when `PoHConfig::stretch_seed` is set.
+pub const SEED_STRETCH_ROUNDS: u32 = 100_000;
+
+/// Tag prefixed to the seed in every stretching round.
+const SEED_STRETCH_TAG: &[u8] = b"poh-yugen/stretch";
+
+/// PBKDF2 iterations used by `InitialSeed::from_passphrase`.
+#[cfg(feature = "pbkdf2")]
+pub const PASSPHRASE_ROUNDS: u32 = 210_000;
+
+impl From<[u8; 64]> for InitialSeed {
+    fn from(seed: [u8; 64]) -> Self {
+        InitialSeed(seed)
+    }
+}
+
+/// Reads a seed from external input, which must be exactly 64 bytes long.
+impl TryFrom<&[u8]> for InitialSeed {
+    type Error = PoHError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        bytes.try_into().map(InitialSeed).map_err(|_| PoHError::InvalidSeedLength { len: bytes.len() })
+    }
+}
+
+/// Seed backed by the operating system's cryptographically secure random number generator.
+#[cfg(feature = "rand")]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct InitialSeed(pub [u8; 64]);
+pub struct RandomSeed {
+    seed: [u8; 64],
+}
+
+#[cfg(feature = "rand")]
+impl RandomSeed {
+    /// Creates a seed filled with 64 fresh random bytes.
+    pub fn new() -> Self {
+        Self { seed: Self::generate_seed() }
+    }
+}
+
+#[cfg(feature = "rand")]
+impl Default for RandomSeed {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "rand")]
+impl Seedable for RandomSeed {
+    fn generate_seed() -> [u8; 64] {
+        use rand::RngCore;
+
+        let mut seed = [0u8; 64];
+        rand::rngs::OsRng.fill_bytes(&mut seed);
+        seed
+    }
+    fn get_seed(&self) -> [u8; 64] {
+        self.seed
+    }
+    fn set_seed(&mut self, seed: [u8; 64]) {
+        self.seed = seed;
+    }
+}
 
 /// Appended Data for the Proof of History (PoH) algorithm.
 /// This structure is used to store additional data that is appended to the PoH entries.
 /// The appended data can be used to include metadata or other information
 /// that is relevant to the PoH process.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+///
+/// Data belongs to a lane, which tells apart logical streams multiplexed onto a
+/// single chain. Lane 0 is the default lane; any other lane is bound into the
+/// hash of its entry.
+///
+/// With the `compression` feature the data may be stored compressed; it is
+/// hashed and returned in its original form either way.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AppendedData {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     data: Vec<u8>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    lane: u8,
+    /// Length of the original data when `data` holds it zstd-compressed.
+    #[cfg(feature = "compression")]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    original_len: Option<usize>,
 }
 
+/// Tag folded into the hash of every entry whose data belongs to a lane other than 0.
+const LANE_TAG: &[u8] = b"poh-yugen/lane";
+/// Tag folded into the hash of every entry carrying a nonce, ahead of the nonce.
+const NONCE_TAG: &[u8] = b"poh-yugen/nonce";
+
 impl<D: Digest> EventData<D> for AppendedData {
+    fn hash_event_data(&self, hasher: D) -> [u8; 32] {
+        self.get_data().hash_event_data(hasher)
+    }
+}
+
+/// Raw bytes are event data in their own right.
+/// Digests shorter than 32 bytes are zero-padded, longer ones are truncated.
+impl<D: Digest> EventData<D> for [u8] {
     fn hash_event_data(&self, mut hasher: D) -> [u8; 32] {
-        hasher.update(&self.data);
+        hasher.update(self);
         let output = hasher.finalize();
         let mut result = [0u8; 32];
-        result.copy_from_slice(&output[..32]);
+        let len = output.len().min(32);
+        result[..len].copy_from_slice(&output[..len]);
         result
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// A 32-byte digest of an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EventHash {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     pub hash: [u8; 32],
 }
 
+impl EventHash {
+    /// Hashes raw event bytes with SHA-256.
+    pub fn from_data(data: &[u8]) -> EventHash {
+        Self::from_event(data, sha2::Sha256::new())
+    }
+    /// Hashes `event` with the given hasher.
+    pub fn from_event<D: Digest, E: EventData<D> + ?Sized>(event: &E, hasher: D) -> EventHash {
+        EventHash { hash: event.hash_event_data(hasher) }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct PoHConfig<D: Digest + Clone> {
     /// The hasher used for the Proof of History (PoH) algorithm.
@@ -129,6 +357,12 @@ pub struct PoHConfig<D: Digest + Clone> {
     /// until the next entry is added. The output size should be chosen
     /// based on the specific requirements of the application and the
     /// capabilities of the underlying hardware.
+    ///
+    /// Every entry hash is cut down to `output_size` bytes. Any fixed-output
+    /// hasher implementing `Digest` supports sizes from 1 up to its natural
+    /// digest length (32 bytes for SHA-256, 64 bytes for SHA-512); larger sizes
+    /// are rejected with `PoHError::OutputSizeMismatch`. Extendable-output
+    /// hashers (SHAKE) do not implement `Digest` and are not supported here.
     pub output_size: usize,
     /// The interval in ticks for the PoH algorithm.
     /// This value determines how often the PoH clock ticks and updates the state.
@@ -142,10 +376,8 @@ pub struct PoHConfig<D: Digest + Clone> {
     /// which provides a good balance between performance and granularity.
     /// The ticks interval should be chosen based on the specific requirements
     /// of the application and the capabilities of the underlying hardware.
-    /// A value of 0 indicates that the PoH clock should not tick, and the PoH
-    /// process should be paused until the next tick is triggered.
-    /// The ticks interval should be set to a positive value to ensure that
-    /// the PoH process continues to run and update the state.
+    /// A value of 0 is rejected when the configuration is validated: it would copy
+    /// every hash unchanged and prove no elapsed work.
     /// A value of 1 indicates that the PoH clock should tick once per microsecond,
     pub tick_interval: u64,
     /// The maximum number of entries allowed in the PoH.
@@ -163,8 +395,10 @@ pub struct PoHConfig<D: Digest + Clone> {
     /// that the PoH process can generate entries and update the state.
     /// A value of None indicates that there is no limit on the number of entries
     /// and the PoH process can continue to generate entries until it is stopped.
-    /// A value of 0 indicates that the PoH process should not generate any entries
-    /// and the PoH state should remain empty until the next entry is added.
+    /// Such chains are generated with `PoHUsage::iter_ticks`, since `PoHUsage::init`
+    /// would never return.
+    /// A value of 0 is rejected when the configuration is validated: the chain
+    /// could never grow past its genesis entry.
     pub max_entries: Option<usize>,
     /// Flags to control the behavior of the PoH algorithm.
     /// These flags determine whether data entries and empty entries are allowed
@@ -176,6 +410,41 @@ pub struct PoHConfig<D: Digest + Clone> {
     /// should be allowed.
     pub allow_data_entries: bool,
     pub allow_empty_entries: bool,
+    /// The largest appended data, in bytes, that a single entry may carry.
+    /// A value of None places no limit on the size of appended data.
+    /// `PoHConfig::new` leaves it unset; use `PoHConfigBuilder::max_data_len`.
+    pub max_data_len: Option<usize>,
+    /// Whether every entry records the hash of its predecessor in `PoHEntry::prev`.
+    /// This roughly doubles the size of each entry in exchange for checking
+    /// entries in isolation. `PoHConfig::new` leaves it disabled.
+    pub store_prev_links: bool,
+    /// Where appended data is mixed into the hash rounds of its entry.
+    /// `PoHConfig::new` uses the default, `DataMixMode::Suffix`.
+    pub data_mix: DataMixMode,
+    /// Byte order of every integer folded into a hash: timestamps, nonces, length
+    /// prefixes and the entry count of commitments. `PoHConfig::new` uses the
+    /// default, `Endianness::Little`.
+    pub endianness: Endianness,
+    /// Whether appended data is stored zstd-compressed. Hashes are computed over
+    /// the original bytes either way. `PoHConfig::new` leaves it disabled.
+    #[cfg(feature = "compression")]
+    pub compress_data: bool,
+    /// Number of most recent entries held in memory. Older entries are pruned as
+    /// new ones are generated, folding them into `PoHUsage::pruned_commitment`
+    /// as `prune_before` does. A value of None holds every entry, and 0 is
+    /// rejected when the configuration is validated. `PoHConfig::new` leaves it unset.
+    pub window: Option<usize>,
+    /// Minimum number of hash rounds between two consecutive data entries. A
+    /// data entry that arrives sooner is hashed with extra rounds, which it
+    /// records in `PoHEntry::interval`, so verification replays them. This bounds
+    /// how quickly events can be recorded independently of `tick_interval`.
+    /// `PoHConfig::new` leaves it at 0, which places no minimum.
+    pub min_interval_between_data: u64,
+    /// Whether the seed is stretched with `SEED_STRETCH_ROUNDS` rounds of
+    /// `InitialSeed::stretched` before it is hashed into the genesis entry. This
+    /// raises the cost of precomputing chains from weak seeds without adding
+    /// entropy. `PoHConfig::new` leaves it disabled.
+    pub stretch_seed: bool,
 
     /// The type of entry to be used in the PoH process.
     /// This value determines the type of data that can be appended to the PoH entries.
@@ -184,41 +453,291 @@ pub struct PoHConfig<D: Digest + Clone> {
     /// - Empty: Indicates that the PoH process should allow empty entries to be added.
     /// - UTF8String: Indicates that the PoH process should allow UTF-8 string entries to be added.
     /// - Hash(usize): Indicates that the PoH process should allow hash entries of a specified size to be added.
+    ///
     /// The tic_entry_type value should be set to a valid TicEntryType value
     /// to ensure that the PoH process can generate entries and update the state.
     pub tick_entry_type: TickEntryType,
 
 }
 
+/// The kind of entries produced by the PoH process.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TickEntryType {
+    /// Entries may carry arbitrary appended data.
     Data,
+    /// Entries never carry appended data.
     Empty,
+    /// Entries may carry arbitrary appended bytes.
     ByteString,
+    /// Appended data must be valid UTF-8 and every entry stores the hex form of
+    /// its hash in `fingerprint`.
     UTF8String,
+    /// Entry hashes are exactly `n` bytes long, overriding `output_size`.
     Hash(usize),
-    /// Event hash types for different sizes
+    /// Entry hashes are fixed 28-byte event digests, overriding `output_size`.
     EventHash28,
+    /// Entry hashes are fixed 32-byte event digests, overriding `output_size`.
     EventHash32,
+    /// Entry hashes are fixed 48-byte event digests, overriding `output_size`.
     EventHash48,
+    /// Entry hashes are fixed 64-byte event digests, overriding `output_size`.
     EventHash64,
 }
 
+/// Where the appended data of an entry is mixed into its hash rounds.
+///
+/// Every mode commits to the data; they differ in which rounds it enters, so a
+/// chain only verifies under the mode it was generated with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DataMixMode {
+    /// Data is hashed before the input of the first round. For the genesis entry
+    /// the initial data is hashed before the seed.
+    Prefix,
+    /// Data is hashed after the input of the final round, and after the seed for
+    /// the genesis entry.
+    #[default]
+    Suffix,
+    /// Data is hashed after the input of every round. The genesis entry is a single
+    /// round and mixes its initial data as in `Suffix`.
+    Interleaved,
+}
+
+/// Byte order of the integers folded into hashes.
+///
+/// Other implementations must use the same order to reproduce a chain. The binary
+/// encoding is always little-endian, independently of this setting.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Endianness {
+    #[default]
+    Little,
+    Big,
+}
+
+impl Endianness {
+    /// The bytes of `value` in this byte order.
+    #[must_use]
+    pub fn u64_bytes(self, value: u64) -> [u8; 8] {
+        match self {
+            Endianness::Little => value.to_le_bytes(),
+            Endianness::Big => value.to_be_bytes(),
+        }
+    }
+}
+
 impl<D: Digest + Clone> PoHConfig<D> {
+    /// Creates a configuration without checking it.
+    ///
+    /// `PoHUsage::new` validates it, e.g. rejecting an `output_size` the hasher
+    /// cannot produce with `PoHError::OutputSizeMismatch`; `validate` runs the same
+    /// checks up front.
     pub fn new(digest: D, output_size: usize, tick_interval: u64, max_entries: Option<usize>, allow_data_entries: bool, allow_empty_entries: bool, tick_entry_type: TickEntryType) -> Self {
         Self {
             // Hasher used for the PoH algorithm with variable output size
             hasher: digest,
-            output_size: output_size,
+            output_size,
 
             tick_interval,
             max_entries,
             
             allow_data_entries,
             allow_empty_entries,
+            max_data_len: None,
+            store_prev_links: false,
+            data_mix: DataMixMode::Suffix,
+            endianness: Endianness::Little,
+            #[cfg(feature = "compression")]
+            compress_data: false,
+            window: None,
+            min_interval_between_data: 0,
+            stretch_seed: false,
             tick_entry_type,
         }
     }
+
+    /// Computes the hash of `entry` from the hash of its predecessor.
+    ///
+    /// Runs `tick_interval` sequential hash rounds starting at `prev`, or the
+    /// entry's own `interval` when it records one. Any appended
+    /// data carried by `entry` is mixed in as selected by `data_mix`. The final
+    /// round then folds in the lane of its data unless it is 0, its nonce, its
+    /// timestamp, its length-prefixed RFC 3339 timestamp, the breaker tag for breaker entries and
+    /// the length-prefixed names of any milestones the entry binds. Integers are
+    /// folded in as 8 bytes in the configured `endianness`. This is the single definition of a link in
+    /// the chain, shared by generation and verification.
+    pub(crate) fn link_hash(&self, prev: &[u8], entry: &PoHEntry) -> Vec<u8> {
+        let data = entry.appended_data.as_ref().map(AppendedData::get_data);
+        let data = data.as_deref();
+        let rounds = self.rounds(entry);
+        let mut output = prev.to_vec();
+        for round in 0..rounds {
+            let last = round + 1 == rounds;
+            let mut hasher = self.hasher.clone();
+            if let (Some(data), DataMixMode::Prefix, 0) = (data, self.data_mix, round) {
+                hasher.update(data);
+            }
+            hasher.update(&output);
+            if let Some(data) = data {
+                let mixed = match self.data_mix {
+                    DataMixMode::Prefix => false,
+                    DataMixMode::Suffix => last,
+                    DataMixMode::Interleaved => true,
+                };
+                if mixed {
+                    hasher.update(data);
+                }
+            }
+            if last {
+                if let Some(lane) = entry.appended_data.as_ref().map(AppendedData::lane).filter(|&lane| lane != 0) {
+                    hasher.update(LANE_TAG);
+                    hasher.update([lane]);
+                }
+                if let Some(nonce) = entry.nonce {
+                    hasher.update(NONCE_TAG);
+                    hasher.update(self.endianness.u64_bytes(nonce));
+                }
+                if let Some(timestamp) = entry.timestamp {
+                    hasher.update(self.endianness.u64_bytes(timestamp));
+                }
+                if let Some(timestamp) = &entry.timestamp_rfc3339 {
+                    hasher.update(self.endianness.u64_bytes(timestamp.len() as u64));
+                    hasher.update(timestamp.as_bytes());
+                }
+                if entry.breaker {
+                    hasher.update(extensions::BREAKER_TAG);
+                }
+                for name in &entry.milestones {
+                    hasher.update(self.endianness.u64_bytes(name.len() as u64));
+                    hasher.update(name.as_bytes());
+                }
+            }
+            output = hasher.finalize().to_vec();
+        }
+        self.truncate(output)
+    }
+
+    /// Hash rounds linking `entry` to its predecessor.
+    pub(crate) fn rounds(&self, entry: &PoHEntry) -> u64 {
+        if entry.interval == 0 {
+            self.tick_interval
+        } else {
+            entry.interval
+        }
+    }
+
+    /// The length in bytes of every entry hash.
+    /// This is `output_size`, unless `tick_entry_type` pins a hash length.
+    /// The digest is truncated to this length, so the hasher must produce at least
+    /// as many bytes.
+    #[must_use]
+    pub fn hash_len(&self) -> usize {
+        match self.tick_entry_type {
+            TickEntryType::Hash(n) => n,
+            TickEntryType::EventHash28 => 28,
+            TickEntryType::EventHash32 => 32,
+            TickEntryType::EventHash48 => 48,
+            TickEntryType::EventHash64 => 64,
+            _ => self.output_size,
+        }
+    }
+
+    /// Cuts a digest down to the configured hash length.
+    pub(crate) fn truncate(&self, mut output: Vec<u8>) -> Vec<u8> {
+        output.truncate(self.hash_len());
+        output
+    }
+
+    /// Checks that the hasher can produce `hash_len` bytes.
+    pub(crate) fn check_output_size(&self) -> Result<(), PoHError> {
+        let hasher = <D as Digest>::output_size();
+        if self.hash_len() > hasher {
+            return Err(PoHError::OutputSizeMismatch { configured: self.hash_len(), hasher });
+        }
+        Ok(())
+    }
+
+    /// Checks the invariants every PoH instance relies on.
+    ///
+    /// `PoHUsage::new` and `PoHConfigBuilder::build` run this check; call it
+    /// directly to reject a configuration as soon as it is loaded. Fails when
+    /// `output_size`, `tick_interval` or `max_entries` is zero, when neither data
+    /// nor empty entries are allowed, when `TickEntryType::Empty` is used without
+    /// allowing empty entries, or when the hasher cannot produce `hash_len` bytes.
+    pub fn validate(&self) -> Result<(), PoHError> {
+        if self.output_size == 0 {
+            return Err(PoHError::InvalidConfig("output_size must be greater than zero"));
+        }
+        if self.tick_interval == 0 {
+            return Err(PoHError::InvalidConfig("tick_interval must be greater than zero"));
+        }
+        if self.max_entries == Some(0) {
+            return Err(PoHError::InvalidConfig("max_entries must be greater than zero"));
+        }
+        if self.window == Some(0) {
+            return Err(PoHError::InvalidConfig("window must be greater than zero"));
+        }
+        if !self.allow_data_entries && !self.allow_empty_entries {
+            return Err(PoHError::InvalidConfig("either data or empty entries must be allowed"));
+        }
+        if self.tick_entry_type == TickEntryType::Empty && !self.allow_empty_entries {
+            return Err(PoHError::InvalidConfig("the Empty entry type requires empty entries to be allowed"));
+        }
+        if self.max_entries.is_some() {
+            self.planned_work()?;
+        }
+        self.check_output_size()
+    }
+
+    /// Sequential hashes behind a chain filled up to `max_entries`: one for the
+    /// genesis entry plus `tick_interval` for every following entry.
+    ///
+    /// The product is computed in 128 bits. Fails with `TooLarge` when it does not
+    /// fit in a `u64`, which `validate` rejects, and with `Unbounded` when
+    /// `max_entries` is `None`.
+    pub fn planned_work(&self) -> Result<u64, PoHError> {
+        let max = self.max_entries.ok_or(PoHError::Unbounded)?;
+        let work = u128::from(self.tick_interval) * max as u128 + 1;
+        u64::try_from(work).map_err(|_| PoHError::TooLarge)
+    }
+
+    /// Checks the `init_data` of a genesis entry: data must be accepted like any
+    /// appended data, and no data requires empty entries to be allowed.
+    pub(crate) fn check_init_data(&self, init_data: Option<&[u8]>) -> Result<(), PoHError> {
+        match init_data {
+            Some(data) if !data.is_empty() => self.check_data(data),
+            _ if !self.allow_empty_entries => Err(PoHError::EmptyNotAllowed),
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that `data` may be appended under the configured entry type.
+    pub(crate) fn check_data(&self, data: &[u8]) -> Result<(), PoHError> {
+        if !self.allow_data_entries {
+            return Err(PoHError::DataNotAllowed);
+        }
+        if let Some(max) = self.max_data_len {
+            if data.len() > max {
+                return Err(PoHError::DataTooLarge { len: data.len(), max });
+            }
+        }
+        match self.tick_entry_type {
+            TickEntryType::Empty => Err(PoHError::EntryTypeMismatch(self.tick_entry_type)),
+            TickEntryType::UTF8String if core::str::from_utf8(data).is_err() => {
+                Err(PoHError::EntryTypeMismatch(self.tick_entry_type))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The fingerprint stored alongside an entry with the given hash.
+    pub(crate) fn fingerprint(&self, hash: &[u8]) -> Option<String> {
+        match self.tick_entry_type {
+            TickEntryType::UTF8String => Some(hex::encode(hash)),
+            _ => None,
+        }
+    }
 }
 
 /* 
@@ -242,177 +761,2362 @@ impl<D: Digest + Clone> Clone for PoHConfig<D> {
 /// of the Proof of History (PoH) algorithm.
 /// It provides methods for generating and verifying PoH entries,
 /// as well as for managing the PoH state.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+///
+/// Chains compare and hash by their id, configuration, entries, extensions,
+/// milestones, pruning commitment, genesis hash, spacing and nonces of data
+/// entries and signatures. The timing of the last run
+/// and the Merkle mountain range cache are left out, so identically generated
+/// chains stay equal.
+#[derive(Clone)]
 pub struct PoHUsage<D: Digest + Clone> {
     id: u64,
     config: PoHConfig<D>,
     state: Vec<PoHEntry>, // Holds the PoH entries
     extensions: Vec<extensions::Extensions>, // Holds any extensions for the PoH process
+    milestones: Vec<(String, usize)>, // Named milestones and the index of the entry they mark
+    #[cfg(feature = "std")]
+    last_run: Option<RunStats>, // Timing of the most recent init run
+    pruned_commitment: Option<[u8; 32]>, // Commitment to the entries removed by prune_before
+    data_rounds: Option<u64>, // Hash rounds since the last data entry as of the first held entry, if one came before
+    last_nonce: Option<u64>, // Nonce of the last data entry up to the first held entry, if any
+    genesis_hash: Option<EntryHash>, // Hash of the genesis entry once it is no longer held
+    #[cfg(feature = "ed25519")]
+    signatures: Vec<(String, [u8; 64])>, // Ed25519 signatures of signed milestones, by name
+    mmr: merkle::Mmr, // Merkle mountain range over the entry hashes, kept while MerkleTree is active
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+impl<D: Digest + Clone + PartialEq> PartialEq for PoHUsage<D> {
+    fn eq(&self, other: &Self) -> bool {
+        #[cfg(feature = "ed25519")]
+        if self.signatures != other.signatures {
+            return false;
+        }
+        self.id == other.id
+            && self.config == other.config
+            && self.state == other.state
+            && self.extensions == other.extensions
+            && self.milestones == other.milestones
+            && self.pruned_commitment == other.pruned_commitment
+            && self.genesis_hash == other.genesis_hash
+            && self.data_rounds == other.data_rounds
+            && self.last_nonce == other.last_nonce
+    }
+}
+
+impl<D: Digest + Clone + Eq> Eq for PoHUsage<D> {}
+
+impl<D: Digest + Clone + core::hash::Hash> core::hash::Hash for PoHUsage<D> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+        self.config.hash(state);
+        self.state.hash(state);
+        self.extensions.hash(state);
+        self.milestones.hash(state);
+        self.pruned_commitment.hash(state);
+        self.genesis_hash.hash(state);
+        self.data_rounds.hash(state);
+        self.last_nonce.hash(state);
+        #[cfg(feature = "ed25519")]
+        self.signatures.hash(state);
+    }
+}
+
+/// An entry of a PoH chain.
+///
+/// Entries are ordered by their position in the chain (`id`). Entries at the same
+/// position, e.g. from forked chains, are ordered by their remaining fields.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PoHEntry {
-    pub id: u64, // The ID of the PoH entry
-    pub timestamp: Option<u64>, // The timestamp of the PoH entry
+    /// Position of the entry in its chain: 0 for the genesis entry, then one more
+    /// than its predecessor. Declared first so that it leads the ordering.
+    pub id: u64,
+    /// Unix time at which the entry was generated. Only recorded while the
+    /// `Timestamp` extension is active, in which case it is folded into the hash.
+    pub timestamp: Option<u64>,
+    /// RFC 3339 time at which the entry was generated. Only recorded while the
+    /// `TimestampRFC3339` extension is active, in which case it is folded into the hash.
+    pub timestamp_rfc3339: Option<String>,
     
     pub fingerprint: Option<String>, // The fingerprint of the PoH entry
     
     // Hash + Appended Data
-    pub hash: Vec<u8>, // The hash of the PoH entry
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    pub hash: EntryHash, // The hash of the PoH entry
+    /// Hash of the previous entry, recorded when `store_prev_links` is enabled so
+    /// the entry can be checked on its own with `PoHUsage::verify_entry`.
+    /// The genesis entry has no predecessor.
+    #[cfg_attr(feature = "serde", serde(default, with = "crate::serde_hex::option"))]
+    pub prev: Option<Vec<u8>>,
     pub appended_data: Option<AppendedData>,
+
+    /// Marks an epoch boundary inserted by the `Breaker` extension.
+    /// Breaker entries never carry data.
+    pub breaker: bool,
+    /// Names of the milestones recorded on the previous entry, bound into this
+    /// entry's hash by the `Milestone` extension.
+    pub milestones: Vec<String>,
+    /// Hash rounds linking this entry to its predecessor when generated with
+    /// `PoHUsage::tick_with_interval`. 0 means the configured `tick_interval`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub interval: u64,
+    /// Nonce of a data entry, bound into its hash. Data entries are numbered
+    /// 0, 1, 2, ... by default, so identical payloads still produce unrelated
+    /// entries; `PoHUsage::append_data_with_nonce` sets it explicitly.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub nonce: Option<u64>,
+    /// Digest of the event appended with `PoHUsage::append_event`. Its bytes are
+    /// the appended data of the entry, which is what the hash commits to.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub event: Option<EventHash>,
 }
 
 impl<D: Digest + Clone> PoHUsage<D> {
-    pub fn new(config: PoHConfig<D>, seed: InitialSeed, init_data: Option<Vec<u8>>, extensions: Vec<extensions::Extensions>) -> Self {
+    /// Creates a new PoH instance whose genesis entry hashes `seed` and `init_data`.
+    ///
+    /// Duplicate extensions are dropped. Fails when the configuration is invalid,
+    /// when extensions conflict, when `init_data` is supplied but not accepted by
+    /// the configuration, or when no `init_data` is supplied but empty entries are
+    /// not allowed.
+    pub fn new(config: PoHConfig<D>, seed: InitialSeed, init_data: Option<Vec<u8>>, mut extensions: Vec<extensions::Extensions>) -> Result<Self, PoHError> {
+        config.validate()?;
+        extensions::normalize(&mut extensions)?;
+        config.check_init_data(init_data.as_deref())?;
+        Ok(Self::new_unchecked(config, seed, init_data, extensions))
+    }
+    /// Same as `new`, failing with `PoHError::SeedReused` when an instance with the
+    /// same `fingerprint` was already created through `new_unique` in this process.
+    ///
+    /// The same seed, initial data and configuration always produce the same
+    /// chain, so two producers sharing them would publish identical, and thus
+    /// relatable, histories. Instances created with `new` are not tracked.
+    #[cfg(feature = "std")]
+    pub fn new_unique(config: PoHConfig<D>, seed: InitialSeed, init_data: Option<Vec<u8>>, extensions: Vec<extensions::Extensions>) -> Result<Self, PoHError> {
+        static USED: std::sync::Mutex<alloc::collections::BTreeSet<[u8; 32]>> = std::sync::Mutex::new(alloc::collections::BTreeSet::new());
+
+        let poh = Self::new(config, seed, init_data, extensions)?;
+        let mut used = USED.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        if !used.insert(poh.fingerprint()) {
+            return Err(PoHError::SeedReused);
+        }
+        Ok(poh)
+    }
+    /// Identifies the origin of the chain: a digest of the configuration and the
+    /// genesis hash, which in turn commits to the seed and initial data.
+    ///
+    /// Chains with equal fingerprints are identical, so callers can use it to spot
+    /// reused seeds without handling the seed itself, which the fingerprint does
+    /// not reveal. Pruning the chain does not change it.
+    #[must_use]
+    pub fn fingerprint(&self) -> [u8; 32] {
+        merkle::hash_32(&self.config.hasher, &[b"poh-yugen/fingerprint", &self.config.config_hash(), self.genesis_hash()])
+    }
+    /// Creates a new PoH instance without validating the configuration or `init_data`.
+    pub fn new_unchecked(config: PoHConfig<D>, seed: InitialSeed, init_data: Option<Vec<u8>>, extensions: Vec<extensions::Extensions>) -> Self {
+        let genesis = Self::genesis_entry(&config, &extensions, seed, init_data);
+        Self::from_parts(config, vec![genesis], extensions)
+    }
+    /// Creates a new PoH instance from `config` alone, with an all-zero seed, no
+    /// `init_data` and no extensions.
+    ///
+    /// Not secure: the genesis entry, and so every entry after it, can be computed
+    /// by anyone who knows the configuration. Meant for tests and examples. Like
+    /// `new_unchecked`, the configuration is not validated.
+    pub fn from_config_only(config: PoHConfig<D>) -> Self {
+        Self::new_unchecked(config, InitialSeed([0; 64]), None, vec![])
+    }
+    /// Discards the chain and starts over from a new genesis entry, exactly as if
+    /// the instance had been created by `new` with the same configuration and
+    /// extensions. The id and the allocation of the state are kept.
+    ///
+    /// Fails, leaving the chain untouched, when `init_data` is rejected as in `new`.
+    pub fn reset(&mut self, seed: InitialSeed, init_data: Option<Vec<u8>>) -> Result<(), PoHError> {
+        self.config.check_init_data(init_data.as_deref())?;
+        let genesis = Self::genesis_entry(&self.config, &self.extensions, seed, init_data);
+        self.state.clear();
+        self.state.push(genesis);
+        self.milestones.clear();
+        #[cfg(feature = "std")]
+        {
+            self.last_run = None;
+        }
+        self.pruned_commitment = None;
+        self.genesis_hash = None;
+        self.data_rounds = first_data_rounds(&self.state);
+        self.last_nonce = None;
+        #[cfg(feature = "ed25519")]
+        self.signatures.clear();
+        self.sync_mmr();
+        Ok(())
+    }
+    /// The genesis entry, hashing `seed` and `init_data`.
+    fn genesis_entry(config: &PoHConfig<D>, extensions: &[extensions::Extensions], seed: InitialSeed, init_data: Option<Vec<u8>>) -> PoHEntry {
         // Appended Data is initialized to None, as it will be created if init_data is provided
         let mut appended_data = None;
+        let seed = if config.stretch_seed { seed.stretched(SEED_STRETCH_ROUNDS) } else { seed };
 
-        // Initialize the PoH with the provided seed
+        // Initialize the PoH with the provided seed, placing any initial data
+        // before or after it as selected by the data mix mode
         let mut hasher = config.hasher.clone();
-        hasher.update(seed.0);
+        if let (Some(init_data), DataMixMode::Prefix) = (&init_data, config.data_mix) {
+            hasher.update(init_data);
+        }
+        hasher.update(seed.as_bytes());
         
         // If there is initial data, hash it and create an AppendedData instance
-        if init_data.is_some() {
-            hasher.update(init_data.clone().unwrap());
-            appended_data = Some(AppendedData::new(init_data.unwrap().to_vec()));
+        if let Some(init_data) = init_data {
+            if config.data_mix != DataMixMode::Prefix {
+                hasher.update(&init_data);
+            }
+            appended_data = Some(AppendedData::new(init_data));
         }
-        let output = hasher.finalize();
+        let output = config.truncate(hasher.finalize().to_vec());
+        let fingerprint = config.fingerprint(&output);
+        #[cfg(feature = "compression")]
+        let appended_data = compression::store(config.compress_data, appended_data);
 
-        Self { 
-            id: 0, 
-            config: config, 
-            state: vec![PoHEntry { id: 0u64, fingerprint: None, timestamp: Some(timestamps::get_current_timestamp()), hash: output.to_vec(), appended_data: appended_data }], 
+        let mut genesis = PoHEntry { fingerprint, hash: EntryHash::computed(output), appended_data, ..PoHEntry::default() };
+        Self::stamp(extensions, &mut genesis);
+        genesis
+    }
+    /// Adopts an existing chain, e.g. one restored from a checkpoint.
+    ///
+    /// The chain is replayed before it is accepted. Fails when the configuration is
+    /// invalid, when extensions conflict, when the chain does not verify, its ids
+    /// included, or when it holds more entries than `max_entries` allows.
+    pub fn from_state(config: PoHConfig<D>, state: Vec<PoHEntry>, mut extensions: Vec<extensions::Extensions>) -> Result<Self, PoHError> {
+        config.validate()?;
+        extensions::normalize(&mut extensions)?;
+        let poh = Self::from_parts(config, state, extensions);
+        poh.verify().map_err(PoHError::Verify)?;
+        poh.ensure_capacity(0)?;
+        Ok(poh)
+    }
+    /// Assembles an instance around an existing, non-empty state.
+    /// Milestones are rebuilt from the names bound into the entries and the
+    /// instance gets a fresh id.
+    pub(crate) fn from_parts(config: PoHConfig<D>, state: Vec<PoHEntry>, extensions: Vec<extensions::Extensions>) -> Self {
+        let milestones = state
+            .iter()
+            .enumerate()
+            .skip(1)
+            .flat_map(|(index, entry)| entry.milestones.iter().map(move |name| (name.clone(), index - 1)))
+            .collect();
+        let data_rounds = first_data_rounds(&state);
+        let last_nonce = state.first().and_then(|first| first.nonce);
+        let mut poh = Self { 
+            id: next_instance_id(), 
+            config, 
+            state, 
             extensions,
-        }
+            milestones,
+            #[cfg(feature = "std")]
+            last_run: None,
+            pruned_commitment: None,
+            genesis_hash: None,
+            data_rounds,
+            last_nonce,
+            #[cfg(feature = "ed25519")]
+            signatures: Vec::new(),
+            mmr: merkle::Mmr::default(),
+        };
+        poh.sync_mmr();
+        poh
+    }
+    /// Whether `extension` is active on this chain.
+    #[must_use]
+    pub fn has_extension(&self, extension: extensions::Extensions) -> bool {
+        self.extensions.contains(&extension)
+    }
+    /// Active extensions.
+    #[must_use]
+    pub fn extensions(&self) -> &[extensions::Extensions] {
+        &self.extensions
     }
+    /// Replaces the active extensions; entries generated from now on follow the
+    /// new set.
+    ///
+    /// Existing entries are left as they are. Every entry records what applied to
+    /// it, namely its timestamps, breaker flag and milestone names, and its hash
+    /// commits to exactly those, so `verify` accepts chains whose extensions
+    /// changed along the way. Duplicates are dropped; fails without changing
+    /// anything when extensions conflict.
+    pub fn set_extensions(&mut self, mut extensions: Vec<extensions::Extensions>) -> Result<(), PoHError> {
+        extensions::normalize(&mut extensions)?;
+        self.extensions = extensions;
+        Ok(())
+    }
+    /// Id of this instance. Every new, adopted or forked instance gets the next id,
+    /// so these are unique within the process. `with_id` replaces it, and
+    /// `from_bytes` restores the id that was encoded, so decoding the same bytes
+    /// twice yields instances with equal ids.
+    #[must_use]
     pub fn get_id(&self) -> u64 {
         self.id
     }
+    /// Replaces the generated id with `id`.
+    #[must_use]
+    pub fn with_id(mut self, id: u64) -> Self {
+        self.id = id;
+        self
+    }
+    /// An id every process derives alike for the same chain: the first 8 bytes
+    /// of the genesis hash read as a little-endian `u64`, zero-padded when the
+    /// hash is shorter.
+    ///
+    /// Chains from the same seed, initial data and configuration share it, so
+    /// independent nodes can refer to a chain without coordinating. Pruning the
+    /// chain does not change it.
+    #[must_use]
+    pub fn deterministic_id(&self) -> u64 {
+        let mut bytes = [0u8; 8];
+        let hash = self.genesis_hash();
+        let len = hash.len().min(8);
+        bytes[..len].copy_from_slice(&hash[..len]);
+        u64::from_le_bytes(bytes)
+    }
+    /// Replaces the generated id with `deterministic_id`.
+    #[must_use]
+    pub fn with_deterministic_id(self) -> Self {
+        let id = self.deterministic_id();
+        self.with_id(id)
+    }
+    #[must_use]
     pub fn get_config(&self) -> &PoHConfig<D> {
         &self.config
     }
-    pub fn init(&mut self) {
-        let initial_state = self.state[0].hash.clone();
-        let interval = self.config.tick_interval;
-        let max_entries = self.config.max_entries.unwrap_or(1000); // Default to 1000 if None
+    /// Sequential hash rounds spent on every entry, i.e. `tick_interval`, unless
+    /// the entry was generated by `tick_with_interval`.
+    /// See `calibrate` to relate it to wall-clock time.
+    #[must_use]
+    pub fn hashes_per_entry(&self) -> u64 {
+        self.config.tick_interval
+    }
+    /// Sequential hashes backing the entries held by the chain, i.e. the sum of
+    /// `PoHEntry::work` over every entry. Pruned entries no longer count.
+    /// Saturates at `u64::MAX` instead of wrapping around.
+    #[must_use]
+    pub fn total_work(&self) -> u64 {
+        self.state.iter().fold(0, |total, entry| total.saturating_add(entry.work(self.config.tick_interval)))
+    }
+    /// Generates `max_entries` empty ticks after the genesis entry.
+    /// Generation is silent; use `init_with_progress` to observe it, or
+    /// `last_run_stats` afterwards to see how long it took.
+    ///
+    /// An unbounded chain (`max_entries: None`) cannot be generated eagerly and is
+    /// rejected with `PoHError::Unbounded`; use `iter_ticks` and stop explicitly.
+    pub fn init(&mut self) -> Result<(), PoHError> {
+        self.init_with_progress(|_, _| {})
+    }
+    /// Same as `init`, calling `on_tick` with the index and contents of every
+    /// entry as soon as it has been generated.
+    pub fn init_with_progress<F: FnMut(usize, &PoHEntry)>(&mut self, mut on_tick: F) -> Result<(), PoHError> {
+        self.config.check_output_size()?;
+        let max_entries = self.config.max_entries.ok_or(PoHError::Unbounded)?;
+        self.ensure_ids(max_entries)?;
 
-        
-        let mut output_of_previous_tick: Vec<u8> = initial_state;
-        for max_ent in 0..max_entries {
-            
-            
-            
-            for _ in 0..interval {
-                let mut hasher = self.config.hasher.clone();
-                hasher.update(output_of_previous_tick);
-                let output = hasher.finalize();
-                output_of_previous_tick = output.to_vec();
-            }
-            // Create a new PoH entry with the output of the previous tick
-            let new_entry = PoHEntry {
-                id: self.state[max_ent].id + 1u64,
-                timestamp: Some(timestamps::get_current_timestamp()),
-                fingerprint: None,
-                hash: output_of_previous_tick.clone(),
-                appended_data: None, // No appended data for now
-            };
-            self.state.push(new_entry);
-            // Print the output of the previous tick
-            println!("Output after {} ticks: {:?}", interval, hex::encode(output_of_previous_tick.clone()));
+        #[cfg(feature = "std")]
+        let start = std::time::Instant::now();
+        for _ in 0..max_entries {
+            // Create a new PoH entry from the output of the previous tick
+            // Ticks produced by init carry no data
+            let index = self.state.len();
+            let new_entry = self.push_entry(PoHEntry::default());
+            on_tick(index, new_entry);
+        }
+        #[cfg(feature = "std")]
+        {
+            self.last_run = Some(RunStats { elapsed: start.elapsed(), entries: max_entries });
         }
+        Ok(())
     }
-    pub fn tick(&mut self, last_tick: PoHEntry, appended_data: Option<Vec<u8>>) {
-        // Implement the tick logic here
-        let interval = self.config.tick_interval;
-        let interval_minus_one = interval - 1;
-
-        let mut output_of_previous_tick: Vec<u8> = last_tick.hash.clone();
-        
-        for _ in 0..interval_minus_one {
-            let mut hasher = self.config.hasher.clone();
-            hasher.update(output_of_previous_tick);
-            let output = hasher.finalize();
-            output_of_previous_tick = output.to_vec();
+    /// Timing of the most recent `init` or `init_with_progress` run, if any.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn last_run_stats(&self) -> Option<RunStats> {
+        self.last_run
+    }
+    /// Extends the chain by `additional_entries` empty ticks after its last entry.
+    ///
+    /// Fails without generating anything when the new entries would exceed
+    /// `max_entries`.
+    pub fn resume(&mut self, additional_entries: usize) -> Result<(), PoHError> {
+        self.config.check_output_size()?;
+        self.ensure_capacity(additional_entries)?;
+        for _ in 0..additional_entries {
+            self.push_entry(PoHEntry::default());
         }
-        let mut hasher = self.config.hasher.clone();
-        hasher.update(output_of_previous_tick.clone());
-        if appended_data.is_some() && self.config.allow_data_entries == true {
-            hasher.update(appended_data.clone().unwrap())
+        Ok(())
+    }
+    /// Appends exactly `n` empty ticks and returns them.
+    ///
+    /// Unlike `resume`, fails with `EmptyNotAllowed` unless the configuration
+    /// allows empty entries. Fails without generating anything when the new
+    /// entries would exceed `max_entries`. With a `window`, only the new entries
+    /// still held are returned.
+    #[must_use = "the result holds the appended ticks, or why none were appended"]
+    pub fn tick_n(&mut self, n: usize) -> Result<&[PoHEntry], PoHError> {
+        if !self.config.allow_empty_entries {
+            return Err(PoHError::EmptyNotAllowed);
         }
-        let output = hasher.finalize();
-        let final_tick = output.to_vec();
-
-        let mut added_data: Option<AppendedData> = None;
+        self.resume(n)?;
+        Ok(&self.state[self.state.len().saturating_sub(n)..])
+    }
+    /// Appends one empty tick hashed with `interval` rounds instead of the
+    /// configured `tick_interval`.
+    ///
+    /// The interval is stored in the entry, so verification replays the same
+    /// number of rounds. Fails when `interval` is zero or when `max_entries` has
+    /// been reached.
+    #[must_use = "the result holds the appended tick, or why it was not appended"]
+    pub fn tick_with_interval(&mut self, interval: u64) -> Result<&PoHEntry, PoHError> {
+        if interval == 0 {
+            return Err(PoHError::InvalidConfig("tick_interval must be greater than zero"));
+        }
+        self.config.check_output_size()?;
+        self.ensure_capacity(1)?;
+        Ok(self.push_entry(PoHEntry { interval, ..PoHEntry::default() }))
+    }
+    /// Branches the chain after the entry at `at_index`.
+    ///
+    /// The fork shares the configuration and extensions, holds a copy of entries
+    /// `0..=at_index` along with the milestones they carry, and gets a fresh id. It
+    /// can then be extended independently of this chain.
+    pub fn fork(&self, at_index: usize) -> Result<PoHUsage<D>, PoHError> {
+        if at_index >= self.state.len() {
+            return Err(PoHError::IndexOutOfRange { index: at_index, len: self.state.len() });
+        }
+        let mut fork = Self::from_parts(self.config.clone(), self.state[..=at_index].to_vec(), self.extensions.clone());
+        fork.data_rounds = self.data_rounds;
+        fork.last_nonce = self.last_nonce;
+        fork.genesis_hash = self.genesis_hash.clone();
+        fork.milestones = self.milestones.iter().filter(|(_, index)| *index <= at_index).cloned().collect();
+        #[cfg(feature = "ed25519")]
+        {
+            fork.signatures = self.signatures.clone();
+            fork.retain_signatures();
+        }
+        Ok(fork)
+    }
+    /// Keeps the first `len` entries and drops the rest, along with the milestones
+    /// marking dropped entries.
+    ///
+    /// The genesis entry is always kept, and nothing happens when `len` is at least
+    /// `self.len()`. The remaining chain still verifies, and milestones marking the
+    /// new tip are bound again by the next entry.
+    pub fn truncate(&mut self, len: usize) {
+        let len = len.max(1);
+        if len >= self.state.len() {
+            return;
+        }
+        self.state.truncate(len);
+        self.milestones.retain(|(_, index)| *index < len);
+        #[cfg(feature = "ed25519")]
+        self.retain_signatures();
+    }
+    /// Appends an entry after `last_tick`, which must be the current tip, carrying
+    /// `appended_data` if any.
+    ///
+    /// With data this is `append_data_entry`, and without it `resume(1)`, so the
+    /// entry gets a nonce, spacing, breakers and pending milestones exactly as
+    /// those do. Fails with `PoHError::NotTip` when `last_tick` is not the tip, and
+    /// otherwise as `append_data_entry` or `resume` do.
+    #[must_use = "the result holds the appended entry, or why it was not appended"]
+    pub fn tick(&mut self, last_tick: PoHEntry, appended_data: Option<Vec<u8>>) -> Result<&PoHEntry, PoHError> {
+        let tip = self.state.last().expect("PoH state always holds the genesis entry");
+        if last_tick.id != tip.id || !last_tick.ct_eq(tip) {
+            return Err(PoHError::NotTip);
+        }
+        match appended_data {
+            Some(data) => self.append_data_entry(data),
+            None => {
+                self.resume(1)?;
+                Ok(self.state.last().expect("PoH state always holds the genesis entry"))
+            }
+        }
+    }
+    /// Appends `data` to the chain as a new entry.
+    ///
+    /// The new entry is hashed from the previous entry with `tick_interval` rounds,
+    /// with `data` folded into the final round along with the next nonce. Fails
+    /// when data entries are not allowed by the configuration, when `data`
+    /// conflicts with `tick_entry_type` or when `max_entries` has been reached.
+    #[must_use = "the result holds the appended entry, or why the data was rejected"]
+    pub fn append_data_entry(&mut self, data: Vec<u8>) -> Result<&PoHEntry, PoHError> {
+        self.append_to_lane(0, data)
+    }
+    /// Hashes `event` with the configured hasher and appends the resulting
+    /// `EventHash` as a data entry, recording it in `PoHEntry::event`.
+    ///
+    /// Fails as `append_data_entry` does for 32 bytes of data.
+    #[must_use = "the result holds the entry recording the event, or why it was rejected"]
+    pub fn append_event<E: EventData<D> + ?Sized>(&mut self, event: &E) -> Result<&PoHEntry, PoHError> {
+        let event = EventHash::from_event(event, self.config.hasher.clone());
+        let nonce = self.next_nonce();
+        self.append_data(0, event.hash.to_vec(), nonce)?;
+        let entry = self.state.last_mut().expect("PoH state always holds the genesis entry");
+        entry.event = Some(event);
+        Ok(entry)
+    }
+    /// Same as `append_data_entry`, placing `data` on `lane`.
+    ///
+    /// Lanes other than 0 are bound into the entry's hash, so an entry cannot be
+    /// moved to another lane without breaking verification.
+    #[must_use = "the result holds the entry appended to the lane, or why the data was rejected"]
+    pub fn append_to_lane(&mut self, lane: u8, data: Vec<u8>) -> Result<&PoHEntry, PoHError> {
+        let nonce = self.next_nonce();
+        self.append_data(lane, data, nonce)
+    }
+    /// Same as `append_data_entry`, with `nonce` instead of the next nonce.
+    ///
+    /// The nonce is stored in the entry and bound into its hash, so two entries
+    /// carrying the same data are still told apart, e.g. by deduplication that
+    /// keys on `(data, nonce)`. Data entries appended afterwards continue from
+    /// `nonce + 1`.
+    #[must_use = "the result holds the entry carrying the nonce, or why the data was rejected"]
+    pub fn append_data_with_nonce(&mut self, data: Vec<u8>, nonce: u64) -> Result<&PoHEntry, PoHError> {
+        self.append_data(0, data, nonce)
+    }
+    fn append_data(&mut self, lane: u8, data: Vec<u8>, nonce: u64) -> Result<&PoHEntry, PoHError> {
+        self.config.check_data(&data)?;
+        self.config.check_output_size()?;
+        self.ensure_capacity(1 + usize::from(self.breaker_due()))?;
 
-        if appended_data.is_some() {
-            let data = appended_data.clone().unwrap();
+        let entry = PoHEntry {
+            appended_data: Some(AppendedData::with_lane(lane, data)),
+            nonce: Some(nonce),
+            ..PoHEntry::default()
+        };
+        Ok(self.push_entry(entry))
+    }
+    /// Nonce of the next data entry: one more than the most recent nonce, or 0.
+    fn next_nonce(&self) -> u64 {
+        self.latest_nonce().map_or(0, |nonce| nonce.wrapping_add(1))
+    }
+    /// Nonce of the most recent data entry, including entries no longer held.
+    pub(crate) fn latest_nonce(&self) -> Option<u64> {
+        self.state.iter().rev().find_map(|entry| entry.nonce).or(self.last_nonce)
+    }
+    /// Number of entries carrying appended data, the genesis entry included when it
+    /// holds initial data.
+    #[must_use]
+    pub fn count_data_entries(&self) -> usize {
+        self.data_entries().count()
+    }
+    /// Number of entries without appended data, i.e. pure ticks and breakers.
+    #[must_use]
+    pub fn count_empty_entries(&self) -> usize {
+        self.state.len() - self.count_data_entries()
+    }
+    /// Entries carrying appended data along with their index, in chain order.
+    pub fn data_entries(&self) -> impl Iterator<Item = (usize, &PoHEntry)> {
+        self.state.iter().enumerate().filter(|(_, entry)| entry.appended_data.is_some())
+    }
+    /// Entries carrying data on `lane`, in chain order.
+    pub fn entries_for_lane(&self, lane: u8) -> impl Iterator<Item = &PoHEntry> {
+        self.state.iter().filter(move |entry| entry.appended_data.as_ref().is_some_and(|data| data.lane == lane))
+    }
+    /// Appends every item as its own data entry, in order, as a single transaction.
+    ///
+    /// Returns the new entries, including any breaker entries inserted between
+    /// them. If any item is rejected, as it would be by `append_data_entry`, none
+    /// of the items are appended. With a `window`, only the new entries still
+    /// held are returned.
+    #[must_use = "the result holds the appended entries, or why the batch was rejected"]
+    pub fn append_data_batch(&mut self, items: Vec<Vec<u8>>) -> Result<&[PoHEntry], PoHError> {
+        for data in &items {
+            self.config.check_data(data)?;
         }
+        self.config.check_output_size()?;
 
+        // Every item takes one entry, plus a breaker entry on epoch boundaries.
+        let mut next_id = self.state.last().map_or(0, |entry| entry.id.saturating_add(1));
+        let mut added = 0;
+        for _ in &items {
+            let entries = 1 + u64::from(extensions::breaker_due(&self.extensions, next_id));
+            next_id = next_id.saturating_add(entries);
+            added += entries as usize;
+        }
+        self.ensure_capacity(added)?;
 
-        // Create a new PoH entry with the output of the previous tick
-        let new_entry = PoHEntry {
-            id: last_tick.id + 1,
-            timestamp: Some(timestamps::get_current_timestamp()),
-            fingerprint: None,
-            hash: final_tick.clone(),
-            appended_data: added_data, // No appended data for now
+        for data in items {
+            let nonce = Some(self.next_nonce());
+            self.push_entry(PoHEntry { appended_data: Some(AppendedData::new(data)), nonce, ..PoHEntry::default() });
+        }
+        Ok(&self.state[self.state.len().saturating_sub(added)..])
+    }
+    /// Appends every item yielded by `iter` as a data entry, each one hashed from
+    /// its predecessor with the configured `tick_interval` rounds.
+    ///
+    /// Items are consumed lazily, so the source may be an unbounded event stream.
+    /// Stops at the first item that `append_data_entry` rejects and returns its
+    /// error; the items before it stay appended.
+    pub fn extend_from_data<I: IntoIterator<Item = Vec<u8>>>(&mut self, iter: I) -> Result<(), PoHError> {
+        for data in iter {
+            self.append_data_entry(data)?;
+        }
+        Ok(())
+    }
+    /// Number of entries generated after the genesis entry, including pruned ones.
+    fn entries_after_genesis(&self) -> usize {
+        self.state.last().map_or(0, |tip| tip.id as usize)
+    }
+    /// Checks that `additional` more entries fit under `max_entries`.
+    /// The genesis entry does not count towards the limit.
+    fn ensure_capacity(&self, additional: usize) -> Result<(), PoHError> {
+        self.ensure_ids(additional)?;
+        match self.config.max_entries {
+            Some(max) if self.entries_after_genesis().saturating_add(additional) > max => Err(PoHError::MaxEntriesReached { max }),
+            _ => Ok(()),
+        }
+    }
+    /// Checks that `additional` more entries still get an id, which an adopted chain
+    /// may have used up.
+    fn ensure_ids(&self, additional: usize) -> Result<(), PoHError> {
+        let tip = self.state.last().map_or(0, |entry| entry.id);
+        match u64::try_from(additional).ok().and_then(|additional| tip.checked_add(additional)) {
+            Some(_) => Ok(()),
+            None => Err(PoHError::IdsExhausted),
+        }
+    }
+    /// Records the current time on `entry` when the `Timestamp` or
+    /// `TimestampRFC3339` extension is active.
+    #[cfg_attr(not(feature = "std"), allow(unused_variables))]
+    fn stamp(extensions: &[extensions::Extensions], entry: &mut PoHEntry) {
+        #[cfg(feature = "std")]
+        if extensions.contains(&extensions::Extensions::Timestamp) {
+            entry.timestamp = Some(timestamps::get_current_timestamp());
+        }
+        #[cfg(feature = "std")]
+        if extensions.contains(&extensions::Extensions::TimestampRFC3339) {
+            entry.timestamp_rfc3339 = Some(timestamps::get_current_timestamp_rfc3339());
+        }
+    }
+    /// Whether the next entry falls on an epoch boundary of the `Breaker` extension.
+    fn breaker_due(&self) -> bool {
+        let next_id = self.state.last().map_or(Some(0), |entry| entry.id.checked_add(1));
+        next_id.is_some_and(|next_id| extensions::breaker_due(&self.extensions, next_id))
+    }
+    /// Names of the milestones marking the current tip, to be bound by the next entry.
+    fn pending_milestones(&self) -> Vec<String> {
+        let tip = self.state.len() - 1;
+        self.milestones.iter().filter(|(_, index)| *index == tip).map(|(name, _)| name.clone()).collect()
+    }
+    /// Links `entry` to `prev`: stamps it, assigns its id and computes its hash.
+    fn link_entry(config: &PoHConfig<D>, extensions: &[extensions::Extensions], prev: &PoHEntry, mut entry: PoHEntry) -> PoHEntry {
+        Self::stamp(extensions, &mut entry);
+        entry.id = prev.id.checked_add(1).expect("callers check the id with ensure_ids");
+        if config.store_prev_links {
+            entry.prev = Some(prev.hash.to_vec());
+        }
+        entry.hash = EntryHash::computed(config.link_hash(&prev.hash, &entry));
+        entry.fingerprint = config.fingerprint(&entry.hash);
+        #[cfg(feature = "compression")]
+        {
+            entry.appended_data = compression::store(config.compress_data, entry.appended_data);
+        }
+        entry
+    }
+    /// Links `entry` to the current tip, hashes it and pushes it onto the state.
+    /// On an epoch boundary an empty entry becomes the breaker, while a data
+    /// entry is preceded by a separate breaker entry.
+    fn push_entry(&mut self, mut entry: PoHEntry) -> &PoHEntry {
+        if self.breaker_due() {
+            if entry.appended_data.is_none() {
+                entry.breaker = true;
+            } else {
+                self.push_entry(PoHEntry { breaker: true, ..PoHEntry::default() });
+            }
+        }
+        entry.milestones = self.pending_milestones();
+        if entry.appended_data.is_some() && entry.interval == 0 {
+            entry.interval = self.data_spacing_interval();
+        }
+        let prev = self.state.last().expect("PoH state always holds the genesis entry");
+        let entry = Self::link_entry(&self.config, &self.extensions, prev, entry);
+        self.state.push(entry);
+        self.apply_window();
+        self.sync_mmr();
+        &self.state[self.state.len() - 1]
+    }
+    /// Rounds the next data entry needs to end at least `min_interval_between_data`
+    /// rounds after the previous data entry, or 0 when `tick_interval` suffices.
+    /// Without any previous data entry there is no minimum.
+    fn data_spacing_interval(&self) -> u64 {
+        let Some(since) = self.data_rounds_at(self.state.len() - 1) else {
+            return 0;
         };
-
+        match self.config.min_interval_between_data.saturating_sub(since) {
+            needed if needed > self.config.tick_interval => needed,
+            _ => 0,
+        }
+    }
+    /// Hash rounds generated since the last data entry, up to the entry at
+    /// `index`, or `None` when no data entry came before. Entries that are no
+    /// longer held are accounted for by `data_rounds`.
+    pub(crate) fn data_rounds_at(&self, index: usize) -> Option<u64> {
+        let held = &self.state[..=index];
+        let (since, after) = match held.iter().rposition(|entry| entry.appended_data.is_some()) {
+            Some(last_data) => (Some(0), &held[last_data + 1..]),
+            None => (self.data_rounds, &held[1..]),
+        };
+        since.map(|since| after.iter().fold(since, |total, entry| total.saturating_add(self.config.rounds(entry))))
+    }
+    /// Prunes the oldest entries beyond the configured `window`.
+    fn apply_window(&mut self) {
+        if let Some(window) = self.config.window {
+            if self.state.len() > window {
+                self.prune_before(self.state.len() - window).expect("the window keeps at least one entry");
+            }
+        }
+    }
+    /// Lazily generates empty ticks following the current tip.
+    ///
+    /// Only the previous hash is kept between steps, so memory use stays constant
+    /// however many entries are produced. The iterator ends once `max_entries` is
+    /// reached and never ends when `max_entries` is `None`. The generated entries
+    /// are not added to the chain.
+    pub fn iter_ticks(&self) -> impl Iterator<Item = PoHEntry> + '_ {
+        let tip = self.state.last().expect("PoH state always holds the genesis entry");
+        let mut prev = PoHEntry { id: tip.id, hash: tip.hash.clone(), ..PoHEntry::default() };
+        let mut milestones = self.pending_milestones();
+        let mut remaining = self.config.max_entries.map(|max| max.saturating_sub(self.entries_after_genesis()));
+        core::iter::from_fn(move || {
+            match remaining.as_mut() {
+                Some(0) => return None,
+                Some(remaining) => *remaining -= 1,
+                None => {}
+            }
+            let next_id = prev.id.checked_add(1)?;
+            let entry = PoHEntry {
+                breaker: extensions::breaker_due(&self.extensions, next_id),
+                milestones: core::mem::take(&mut milestones),
+                ..PoHEntry::default()
+            };
+            let entry = Self::link_entry(&self.config, &self.extensions, &prev, entry);
+            prev = PoHEntry { id: entry.id, hash: entry.hash.clone(), ..PoHEntry::default() };
+            Some(entry)
+        })
     }
-    pub fn genesis(&mut self) {
-        // Implement the genesis logic here
-        self.tick(self.state[0].clone(), None);
+    /// The genesis entry, hashing the seed and initial data.
+    /// After `prune_before` this is the oldest retained entry instead.
+    #[must_use]
+    pub fn genesis(&self) -> &PoHEntry {
+        self.state.first().expect("PoH state always holds the genesis entry")
     }
+    /// Hash of the genesis entry.
+    ///
+    /// `prune_before` keeps the hash when it removes the genesis entry, and
+    /// `from_snapshot` takes it from the snapshot, so it stays the same for the
+    /// whole chain.
+    #[must_use]
+    pub fn genesis_hash(&self) -> &[u8] {
+        self.genesis_hash.as_deref().unwrap_or(&self.genesis().hash)
+    }
+    /// Whether both chains start from the same genesis entry, i.e. were created
+    /// from the same seed, initial data and configuration, even if either was
+    /// pruned since.
+    #[must_use]
+    pub fn same_genesis(&self, other: &Self) -> bool {
+        verify::hashes_equal(self.genesis_hash(), other.genesis_hash())
+    }
+    #[must_use]
     pub fn get_state(&self) -> &Vec<PoHEntry> {
         &self.state
     }
+    /// The hash of every entry, in chain order.
+    #[must_use]
+    pub fn hashes(&self) -> Vec<Vec<u8>> {
+        self.state.iter().map(|entry| entry.hash.to_vec()).collect()
+    }
+    /// The hash of every entry as a 32-byte array, in chain order.
+    /// Fails if any entry hash is not exactly 32 bytes long.
+    pub fn hashes_fixed(&self) -> Result<Vec<[u8; 32]>, PoHError> {
+        self.state
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                entry.hash.as_slice().try_into().map_err(|_| PoHError::HashLengthMismatch { index, len: entry.hash.len(), expected: 32 })
+            })
+            .collect()
+    }
+    /// Entry at `index`, where index 0 is the genesis entry.
+    #[must_use]
+    pub fn entry(&self, index: usize) -> Option<&PoHEntry> {
+        self.state.get(index)
+    }
+    /// Overlapping runs of `size` consecutive entries, in chain order.
+    ///
+    /// ```
+    /// use poh_yugen::{InitialSeed, PoHConfig, PoHUsage};
+    /// use sha2::{Digest, Sha256};
+    ///
+    /// let config = PoHConfig::builder(Sha256::new()).tick_interval(10).max_entries(Some(3)).build().unwrap();
+    /// let mut poh = PoHUsage::new(config, InitialSeed([1; 64]), None, vec![]).unwrap();
+    /// poh.init().unwrap();
+    /// let starts: Vec<u64> = poh.entry_windows(2).map(|window| window[0].id).collect();
+    /// assert_eq!(starts, [0, 1, 2]);
+    /// ```
+    ///
+    /// Panics if `size` is 0.
+    pub fn entry_windows(&self, size: usize) -> impl Iterator<Item = &[PoHEntry]> {
+        self.state.windows(size)
+    }
+    /// Consecutive runs of `size` entries, in chain order; the last run may be
+    /// shorter.
+    ///
+    /// ```
+    /// use poh_yugen::{InitialSeed, PoHConfig, PoHUsage};
+    /// use sha2::{Digest, Sha256};
+    ///
+    /// let config = PoHConfig::builder(Sha256::new()).tick_interval(10).max_entries(Some(4)).build().unwrap();
+    /// let mut poh = PoHUsage::new(config, InitialSeed([1; 64]), None, vec![]).unwrap();
+    /// poh.init().unwrap();
+    /// let sizes: Vec<usize> = poh.entry_chunks(2).map(<[_]>::len).collect();
+    /// assert_eq!(sizes, [2, 2, 1]);
+    /// ```
+    ///
+    /// Panics if `size` is 0.
+    pub fn entry_chunks(&self, size: usize) -> impl Iterator<Item = &[PoHEntry]> {
+        self.state.chunks(size)
+    }
+    /// Index of the first entry at which this chain and `other` differ, in hash or
+    /// appended data.
+    ///
+    /// Returns `None` when the chains are equal or one is a prefix of the other;
+    /// `is_strict_prefix_of` tells those apart. Entries are compared by position,
+    /// so both chains are expected to start at the same entry.
+    #[must_use]
+    pub fn diverges_at(&self, other: &PoHUsage<D>) -> Option<usize> {
+        self.state
+            .iter()
+            .zip(&other.state)
+            .position(|(a, b)| a.hash != b.hash || a.appended_data != b.appended_data)
+    }
+    /// Whether `other` extends this chain by at least one entry.
+    #[must_use]
+    pub fn is_strict_prefix_of(&self, other: &PoHUsage<D>) -> bool {
+        self.state.len() < other.state.len() && self.diverges_at(other).is_none()
+    }
+    /// Index of the entry whose hash is `hash`, if it is part of the chain.
+    #[must_use]
+    pub fn index_of(&self, hash: &[u8]) -> Option<usize> {
+        self.state.iter().position(|entry| *entry.hash == *hash)
+    }
+    /// The entry whose hash is `hash`, if it is part of the chain.
+    #[must_use]
+    pub fn find(&self, hash: &[u8]) -> Option<&PoHEntry> {
+        self.index_of(hash).map(|index| &self.state[index])
+    }
+    /// Number of entries in the chain, including the genesis entry.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.state.len()
+    }
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.state.is_empty()
+    }
+    /// The genesis entry.
+    #[must_use]
+    pub fn first(&self) -> Option<&PoHEntry> {
+        self.state.first()
+    }
+    /// The most recent entry.
+    #[must_use]
+    pub fn last(&self) -> Option<&PoHEntry> {
+        self.state.last()
+    }
+    /// Hash of the most recent entry.
+    #[must_use]
+    pub fn tip(&self) -> Option<&[u8]> {
+        self.last().map(|entry| entry.hash.as_slice())
+    }
+    /// Same as `tip`, hex-encoded.
+    #[must_use]
+    pub fn tip_hex(&self) -> Option<String> {
+        self.tip().map(hex::encode)
+    }
+    /// Hash of every entry, in chain order, truncated to its first `n` bytes.
+    ///
+    /// A compact view for protocols with little room per hash. The views are not
+    /// independently verifiable: every link is computed from the full hash of its
+    /// predecessor, so replaying the chain needs the untruncated entries.
+    ///
+    /// Panics if `n` is greater than `hash_len`.
+    #[must_use]
+    pub fn view_truncated(&self, n: usize) -> Vec<Vec<u8>> {
+        let hash_len = self.config.hash_len();
+        assert!(n <= hash_len, "cannot truncate {} byte hashes to {} bytes", hash_len, n);
+        self.state.iter().map(|entry| entry.hash[..n].to_vec()).collect()
+    }
+    /// Marks the current tip as a named milestone.
+    ///
+    /// The name is bound into the hash of the next entry, so the milestone cannot be
+    /// moved or renamed without breaking verification. Requires the `Milestone`
+    /// extension and a name that has not been used before.
+    pub fn add_milestone(&mut self, name: String) -> Result<(), PoHError> {
+        if !self.has_extension(extensions::Extensions::Milestone) {
+            return Err(PoHError::ExtensionNotActive(extensions::Extensions::Milestone));
+        }
+        if self.milestone(&name).is_some() {
+            return Err(PoHError::DuplicateMilestone(name));
+        }
+        self.milestones.push((name, self.state.len() - 1));
+        Ok(())
+    }
+    /// Index of the entry marked by the milestone called `name`.
+    #[must_use]
+    pub fn milestone(&self, name: &str) -> Option<usize> {
+        self.milestones.iter().find(|(milestone, _)| milestone == name).map(|(_, index)| *index)
+    }
+    /// Every milestone with the index of the entry it marks, in the order they were added.
+    #[must_use]
+    pub fn milestones(&self) -> &[(String, usize)] {
+        &self.milestones
+    }
+    /// Splits the chain into epochs, each starting at the genesis entry or a breaker entry.
+    /// Without the `Breaker` extension the whole chain is a single epoch.
+    pub fn epochs(&self) -> impl Iterator<Item = &[PoHEntry]> {
+        let mut rest = &self.state[..];
+        core::iter::from_fn(move || {
+            if rest.is_empty() {
+                return None;
+            }
+            let end = rest.iter().skip(1).position(|entry| entry.breaker).map_or(rest.len(), |position| position + 1);
+            let (epoch, tail) = rest.split_at(end);
+            rest = tail;
+            Some(epoch)
+        })
+    }
 }
 
 
+impl<'a, D: Digest + Clone> IntoIterator for &'a PoHUsage<D> {
+    type Item = &'a PoHEntry;
+    type IntoIter = core::slice::Iter<'a, PoHEntry>;
 
-impl PoHEntry {
-    pub fn to_hex_string(&self) -> String {
-        hex::encode(&self.hash)
+    /// Iterates over the entries in chain order.
+    fn into_iter(self) -> Self::IntoIter {
+        self.state.iter()
     }
 }
 
-// Appended Data implementation
+impl<D: Digest + Clone> IntoIterator for PoHUsage<D> {
+    type Item = PoHEntry;
+    type IntoIter = vec::IntoIter<PoHEntry>;
 
-impl AppendedData {
-    pub fn new(data: Vec<u8>) -> Self {
-        Self { data }
-    }
-    pub fn get_data(&self) -> &[u8] {
-        &self.data
-    }
-    pub fn to_vec(&self) -> Vec<u8> {
-        self.data.clone()
+    /// Consumes the chain, yielding its entries in chain order.
+    fn into_iter(self) -> Self::IntoIter {
+        self.state.into_iter()
     }
 }
 
-#[test]
-fn run() {
-    let config = PoHConfig::new(Sha256::new(), 32, 100, Some(10), true, true, TickEntryType::Data);
-    let seed = InitialSeed([0; 64]);
-    let mut poh = PoHUsage::new(config, seed, Some(vec![1, 2, 3]), vec![]);
-    println!("{:?}", poh.state);
-    println!("Initializing PoH...");
-    
-    // Initialize the PoH process
-    poh.init();
-    println!("{:?}", poh.state);
-    println!("LEN: {}", poh.state.len());
+/// Read-only access to the entries as a slice. There is no `DerefMut`: entries
+/// are only added through the generation methods, which keep the chain linked.
+impl<D: Digest + Clone> core::ops::Deref for PoHUsage<D> {
+    type Target = [PoHEntry];
 
+    fn deref(&self) -> &[PoHEntry] {
+        &self.state
+    }
 }
 
-#[test]
-fn run2() {
+impl<D: Digest + Clone> AsRef<[PoHEntry]> for PoHUsage<D> {
+    fn as_ref(&self) -> &[PoHEntry] {
+        &self.state
+    }
+}
+
+impl PoHEntry {
+    #[must_use]
+    pub fn to_hex_string(&self) -> String {
+        hex::encode(&self.hash)
+    }
+    /// Rebuilds an entry from the hex string produced by `to_hex_string`.
+    ///
+    /// Only the hash is restored; every other field keeps its default.
+    pub fn from_hex(s: &str) -> Result<PoHEntry, hex::FromHexError> {
+        Self::from_hex_with_data(s, None)
+    }
+    /// Like `from_hex`, additionally decoding the entry's appended data from hex.
+    pub fn from_hex_with_data(s: &str, appended_data: Option<&str>) -> Result<PoHEntry, hex::FromHexError> {
+        Ok(PoHEntry {
+            hash: hex::decode(s)?.into(),
+            appended_data: appended_data.map(hex::decode).transpose()?.map(AppendedData::new),
+            ..Default::default()
+        })
+    }
+    /// Number of sequential hashes this entry represents in a chain generated with
+    /// `tick_interval`: a single hash for the genesis entry, then the entry's own
+    /// `interval` if it records one and `tick_interval` rounds otherwise.
+    #[must_use]
+    pub fn work(&self, tick_interval: u64) -> u64 {
+        match (self.id, self.interval) {
+            (0, _) => 1,
+            (_, 0) => tick_interval,
+            (_, interval) => interval,
+        }
+    }
+    /// Unix time recorded by the `Timestamp` extension, if any.
+    #[must_use]
+    pub fn timestamp(&self) -> Option<u64> {
+        self.timestamp
+    }
+    /// RFC 3339 time recorded by the `TimestampRFC3339` extension, if any.
+    #[must_use]
+    pub fn timestamp_rfc3339(&self) -> Option<&str> {
+        self.timestamp_rfc3339.as_deref()
+    }
+    /// Appended data, if any. Borrowed unless it is stored compressed.
+    #[must_use]
+    pub fn data_bytes(&self) -> Option<Cow<'_, [u8]>> {
+        self.appended_data.as_ref().map(AppendedData::get_data)
+    }
+    /// Appended data as hex, if any.
+    #[must_use]
+    pub fn data_hex(&self) -> Option<String> {
+        self.data_bytes().map(hex::encode)
+    }
+    /// Appended data as UTF-8, if any, e.g. for `TickEntryType::UTF8String` chains.
+    #[must_use]
+    pub fn data_as_str(&self) -> Option<Result<Cow<'_, str>, core::str::Utf8Error>> {
+        self.data_bytes().map(|data| match data {
+            Cow::Borrowed(bytes) => core::str::from_utf8(bytes).map(Cow::Borrowed),
+            Cow::Owned(bytes) => String::from_utf8(bytes).map(Cow::Owned).map_err(|err| err.utf8_error()),
+        })
+    }
+}
+
+// Appended Data implementation
+
+impl AppendedData {
+    pub fn new(data: Vec<u8>) -> Self {
+        Self::with_lane(0, data)
+    }
+    /// Data on `lane` instead of the default lane 0.
+    pub fn with_lane(lane: u8, data: Vec<u8>) -> Self {
+        Self {
+            data,
+            lane,
+            #[cfg(feature = "compression")]
+            original_len: None,
+        }
+    }
+    #[must_use]
+    pub fn lane(&self) -> u8 {
+        self.lane
+    }
+    /// The original data, decompressed if it is stored compressed.
+    #[must_use]
+    pub fn get_data(&self) -> Cow<'_, [u8]> {
+        #[cfg(feature = "compression")]
+        if let Some(original_len) = self.original_len {
+            return compression::decompress(&self.data, original_len);
+        }
+        Cow::Borrowed(&self.data)
+    }
+    #[must_use]
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.get_data().into_owned()
+    }
+    /// Length of the original data.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        #[cfg(feature = "compression")]
+        if let Some(original_len) = self.original_len {
+            return original_len;
+        }
+        self.data.len()
+    }
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Generates a SHA-256 chain of `count` ticks after the genesis entry derived from
+/// `seed`, running `interval` hash rounds per entry.
+///
+/// The returned entries start with the genesis entry, so there are `count + 1`
+/// of them. Use `PoHUsage` directly for any other configuration.
+///
+/// # Panics
+///
+/// Panics if `interval` is zero.
+pub fn generate(seed: [u8; 64], interval: u64, count: usize) -> Vec<PoHEntry> {
+    let config = PoHConfig::new(sha2::Sha256::new(), 32, interval, Some(count), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed(seed), None, vec![]).expect("interval must be greater than zero");
+    poh.resume(count).expect("count fits under max_entries");
+    poh.state
+}
+
+/// `data_rounds` of a chain whose first held entry is `state[0]`, as far as that
+/// entry tells: 0 after a data entry, otherwise no preceding data entry.
+fn first_data_rounds(state: &[PoHEntry]) -> Option<u64> {
+    state.first().and_then(|first| first.appended_data.as_ref()).map(|_| 0)
+}
+
+/// Hands out process-wide unique instance ids, starting at 1.
+fn next_instance_id() -> u64 {
+    static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+    NEXT_ID.fetch_add(1, Ordering::Relaxed) as u64
+}
+
+#[test]
+fn run() {
     let config = PoHConfig::new(Sha256::new(), 32, 100, Some(10), true, true, TickEntryType::Data);
-    let seed = InitialSeed([1; 64]);
-    let mut poh = PoHUsage::new(config, seed, Some(vec![1, 2, 3]), vec![]);
+    let seed = InitialSeed([0; 64]);
+    let mut poh = PoHUsage::new(config, seed, Some(vec![1, 2, 3]), vec![]).unwrap();
     println!("{:?}", poh.state);
     println!("Initializing PoH...");
     
     // Initialize the PoH process
-    poh.genesis();
-}
\ No newline at end of file
+    poh.init().unwrap();
+    println!("{:?}", poh.state);
+    println!("LEN: {}", poh.state.len());
+}
+
+#[test]
+fn init_reports_progress() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(4), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed([6; 64]), None, vec![]).unwrap();
+    let mut seen = Vec::new();
+    poh.init_with_progress(|index, entry| seen.push((index, entry.hash.clone()))).unwrap();
+    assert_eq!(seen.len(), 4);
+    for (index, hash) in seen {
+        assert_eq!(poh.state[index].hash, hash);
+    }
+
+}
+
+#[test]
+fn run2() {
+    let config = PoHConfig::new(Sha256::new(), 32, 100, Some(10), true, true, TickEntryType::Data);
+    let seed = InitialSeed([1; 64]);
+    let poh = PoHUsage::new(config, seed, Some(vec![1, 2, 3]), vec![]).unwrap();
+    println!("{:?}", poh.state);
+    assert_eq!(poh.genesis().appended_data, Some(AppendedData::new(vec![1, 2, 3])));
+    assert_eq!(poh.genesis_hash(), poh.state[0].hash.as_slice());
+}
+
+#[test]
+fn verify_replays_chain() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(6), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed([2; 64]), None, vec![]).unwrap();
+    poh.resume(5).unwrap();
+    let tip = poh.state.last().unwrap().clone();
+    poh.tick(tip, Some(b"event".to_vec())).unwrap();
+    assert!(poh.verify().is_ok());
+
+    poh.state[3].hash[0] ^= 1;
+    match poh.verify() {
+        Err(VerifyError::HashMismatch { index, actual, .. }) => {
+            assert_eq!(index, 3);
+            assert_eq!(actual, poh.state[3].hash);
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn append_data_entry_respects_config() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(2), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed([3; 64]), None, vec![]).unwrap();
+    let entry = poh.append_data_entry(b"first".to_vec()).unwrap();
+    assert_eq!(entry.id, 1);
+    assert_eq!(&*entry.appended_data.as_ref().unwrap().get_data(), b"first");
+    poh.append_data_entry(b"second".to_vec()).unwrap();
+    assert_eq!(poh.append_data_entry(b"third".to_vec()), Err(PoHError::MaxEntriesReached { max: 2 }));
+    assert!(poh.verify().is_ok());
+
+    let config = PoHConfig::new(Sha256::new(), 32, 10, None, false, true, TickEntryType::Empty);
+    let mut poh = PoHUsage::new(config, InitialSeed([3; 64]), None, vec![]).unwrap();
+    assert_eq!(poh.append_data_entry(b"data".to_vec()), Err(PoHError::DataNotAllowed));
+}
+
+#[test]
+fn output_size_truncates_entries() {
+    let config = PoHConfig::new(Sha256::new(), 16, 10, Some(3), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config.clone(), InitialSeed([4; 64]), None, vec![]).unwrap();
+    poh.init().unwrap();
+    assert!(poh.state.iter().all(|entry| entry.hash.len() == 16));
+    assert!(poh.verify().is_ok());
+
+    let mut poh = PoHUsage::new(config, InitialSeed([4; 64]), None, vec![]).unwrap();
+    assert_eq!(poh.append_data_entry(b"data".to_vec()).unwrap().hash.len(), 16);
+    assert!(poh.verify().is_ok());
+
+    let config = PoHConfig::new(Sha256::new(), 64, 10, Some(3), true, true, TickEntryType::Data);
+    assert_eq!(config.validate(), Err(PoHError::OutputSizeMismatch { configured: 64, hasher: 32 }));
+    assert_eq!(PoHUsage::new(config.clone(), InitialSeed([4; 64]), None, vec![]).err(), Some(PoHError::OutputSizeMismatch { configured: 64, hasher: 32 }));
+    let mut poh = PoHUsage::new_unchecked(config, InitialSeed([4; 64]), None, vec![]);
+    assert_eq!(poh.init(), Err(PoHError::OutputSizeMismatch { configured: 64, hasher: 32 }));
+}
+
+#[test]
+fn tick_entry_types_shape_entries() {
+    let seed = InitialSeed([5; 64]);
+
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(2), true, true, TickEntryType::Empty);
+    let mut poh = PoHUsage::new(config, seed.clone(), None, vec![]).unwrap();
+    assert_eq!(poh.append_data_entry(b"data".to_vec()), Err(PoHError::EntryTypeMismatch(TickEntryType::Empty)));
+    poh.init().unwrap();
+    assert!(poh.state.iter().all(|entry| entry.appended_data.is_none()));
+
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(2), true, true, TickEntryType::UTF8String);
+    let mut poh = PoHUsage::new(config, seed.clone(), None, vec![]).unwrap();
+    assert_eq!(poh.append_data_entry(vec![0xff, 0xfe]), Err(PoHError::EntryTypeMismatch(TickEntryType::UTF8String)));
+    let entry = poh.append_data_entry("héllo".as_bytes().to_vec()).unwrap();
+    assert_eq!(entry.fingerprint, Some(entry.to_hex_string()));
+
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(2), true, true, TickEntryType::Hash(20));
+    let mut poh = PoHUsage::new(config, seed.clone(), None, vec![]).unwrap();
+    poh.init().unwrap();
+    assert!(poh.state.iter().all(|entry| entry.hash.len() == 20));
+    assert!(poh.verify().is_ok());
+
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(2), true, true, TickEntryType::Hash(40));
+    assert_eq!(PoHUsage::new(config, seed.clone(), None, vec![]).err(), Some(PoHError::OutputSizeMismatch { configured: 40, hasher: 32 }));
+
+    for entry_type in [TickEntryType::Data, TickEntryType::ByteString] {
+        let config = PoHConfig::new(Sha256::new(), 32, 10, Some(2), true, true, entry_type);
+        let mut poh = PoHUsage::new(config, seed.clone(), None, vec![]).unwrap();
+        poh.append_data_entry(vec![0xff, 0x00]).unwrap();
+        assert!(poh.verify().is_ok());
+    }
+}
+
+#[test]
+fn new_rejects_misconfiguration() {
+    let seed = InitialSeed([7; 64]);
+
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(2), false, true, TickEntryType::Data);
+    assert_eq!(PoHUsage::new(config.clone(), seed.clone(), Some(vec![1]), vec![]).err(), Some(PoHError::DataNotAllowed));
+    assert!(PoHUsage::new(config, seed.clone(), None, vec![]).is_ok());
+
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(2), true, false, TickEntryType::Data);
+    assert_eq!(PoHUsage::new(config.clone(), seed.clone(), None, vec![]).err(), Some(PoHError::EmptyNotAllowed));
+    assert_eq!(PoHUsage::new(config.clone(), seed.clone(), Some(vec![]), vec![]).err(), Some(PoHError::EmptyNotAllowed));
+    assert!(PoHUsage::new(config, seed.clone(), Some(vec![1]), vec![]).is_ok());
+
+    let config = PoHConfig::new(Sha256::new(), 0, 10, Some(2), true, true, TickEntryType::Data);
+    assert!(matches!(PoHUsage::new(config, seed.clone(), None, vec![]), Err(PoHError::InvalidConfig(_))));
+
+    let config = PoHConfig::new(Sha256::new(), 32, 0, Some(2), true, true, TickEntryType::Data);
+    assert_eq!(
+        PoHUsage::new(config, seed, None, vec![]).err(),
+        Some(PoHError::InvalidConfig("tick_interval must be greater than zero"))
+    );
+    assert!(PoHConfig::builder(Sha256::new()).tick_interval(0).build().is_err());
+}
+
+#[test]
+fn validate_checks_configs_standalone() {
+    let valid = PoHConfig::new(Sha256::new(), 32, 10, Some(2), true, true, TickEntryType::Data);
+    assert_eq!(valid.validate(), Ok(()));
+
+    let invalid = [
+        (PoHConfig { max_entries: Some(0), ..valid.clone() }, "max_entries must be greater than zero"),
+        (PoHConfig { allow_data_entries: false, allow_empty_entries: false, ..valid.clone() }, "either data or empty entries must be allowed"),
+        (
+            PoHConfig { allow_empty_entries: false, tick_entry_type: TickEntryType::Empty, ..valid.clone() },
+            "the Empty entry type requires empty entries to be allowed",
+        ),
+    ];
+    for (config, reason) in invalid {
+        assert_eq!(config.validate(), Err(PoHError::InvalidConfig(reason)));
+    }
+    assert_eq!(
+        PoHConfig { output_size: 33, ..valid.clone() }.validate(),
+        Err(PoHError::OutputSizeMismatch { configured: 33, hasher: 32 })
+    );
+    assert_eq!(PoHConfig { max_entries: None, ..valid }.validate(), Ok(()));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trip_keeps_chain_verifiable() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(4), true, true, TickEntryType::Data);
+    let seed = InitialSeed([8; 64]);
+    let mut poh = PoHUsage::new(config, seed.clone(), Some(vec![1, 2, 3]), vec![]).unwrap();
+    poh.init().unwrap();
+
+    let json = serde_json::to_string(&poh.state).unwrap();
+    let restored: Vec<PoHEntry> = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, poh.state);
+    poh.state = restored;
+    assert!(poh.verify().is_ok());
+
+    let linked = PoHEntry { id: 1, hash: vec![2; 32].into(), prev: Some(vec![1; 32]), ..PoHEntry::default() };
+    let json = serde_json::to_string(&linked).unwrap();
+    assert!(json.contains(&hex::encode([1; 32])));
+    assert_eq!(serde_json::from_str::<PoHEntry>(&json).unwrap(), linked);
+
+    let json = serde_json::to_string(&seed).unwrap();
+    assert_eq!(json, format!("\"{}\"", hex::encode([8; 64])));
+    assert_eq!(serde_json::from_str::<InitialSeed>(&json).unwrap(), seed);
+    assert!(serde_json::from_str::<InitialSeed>("\"0011\"").is_err());
+
+    let event = EventHash { hash: [9; 32] };
+    let json = serde_json::to_string(&event).unwrap();
+    assert_eq!(serde_json::from_str::<EventHash>(&json).unwrap(), event);
+    let entry_type: TickEntryType = serde_json::from_str(&serde_json::to_string(&TickEntryType::Hash(20)).unwrap()).unwrap();
+    assert_eq!(entry_type, TickEntryType::Hash(20));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn timestamp_extension_binds_time() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(3), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config.clone(), InitialSeed([12; 64]), None, vec![extensions::Extensions::Timestamp]).unwrap();
+    assert_eq!(poh.last_run_stats(), None);
+    poh.init().unwrap();
+    assert_eq!(poh.last_run_stats().map(|stats| stats.entries), Some(3));
+    poh.truncate(3);
+    let tip = poh.state.last().unwrap().clone();
+    poh.tick(tip, Some(b"stamped".to_vec())).unwrap();
+    assert!(poh.state.iter().all(|entry| entry.timestamp().is_some()));
+    assert!(poh.verify().is_ok());
+
+    let stamp = poh.state[2].timestamp.unwrap();
+    poh.state[2].timestamp = Some(stamp + 1);
+    assert!(matches!(poh.verify(), Err(VerifyError::HashMismatch { index: 2, .. })));
+
+    let mut rfc3339 = PoHUsage::new(config.clone(), InitialSeed([12; 64]), None, vec![extensions::Extensions::TimestampRFC3339]).unwrap();
+    rfc3339.init().unwrap();
+    assert!(rfc3339.verify().is_ok());
+    for entry in &rfc3339.state {
+        let unix = timestamps::rfc3339_to_unix(entry.timestamp_rfc3339().unwrap()).unwrap();
+        assert!(unix.abs_diff(timestamps::get_current_timestamp()) <= 5);
+    }
+    rfc3339.state[1].timestamp_rfc3339 = Some("2000-01-01T00:00:00+00:00".to_string());
+    assert!(matches!(rfc3339.verify(), Err(VerifyError::HashMismatch { index: 1, .. })));
+
+    let mut plain = PoHUsage::new(config, InitialSeed([12; 64]), None, vec![]).unwrap();
+    plain.init().unwrap();
+    assert!(plain.state.iter().all(|entry| entry.timestamp().is_none() && entry.timestamp_rfc3339().is_none()));
+}
+
+#[test]
+fn breaker_extension_splits_epochs() {
+    let config = PoHConfig::new(Sha256::new(), 32, 5, Some(8), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed([13; 64]), None, vec![extensions::Extensions::Breaker(3)]).unwrap();
+    poh.init().unwrap();
+    let breakers: Vec<u64> = poh.state.iter().filter(|entry| entry.breaker).map(|entry| entry.id).collect();
+    assert_eq!(breakers, vec![3, 6]);
+    assert!(poh.verify().is_ok());
+
+    let epochs: Vec<Vec<u64>> = poh.epochs().map(|epoch| epoch.iter().map(|entry| entry.id).collect()).collect();
+    assert_eq!(epochs, vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8]]);
+
+    poh.state[3].breaker = false;
+    assert!(matches!(poh.verify(), Err(VerifyError::HashMismatch { index: 3, .. })));
+
+    let config = PoHConfig::new(Sha256::new(), 32, 5, Some(3), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed([13; 64]), None, vec![extensions::Extensions::Breaker(2)]).unwrap();
+    poh.append_data_entry(b"one".to_vec()).unwrap();
+    assert_eq!(poh.append_data_entry(b"two".to_vec()).unwrap().id, 3);
+    assert!(poh.state[2].breaker && poh.state[2].appended_data.is_none());
+    assert_eq!(poh.append_data_entry(b"three".to_vec()), Err(PoHError::MaxEntriesReached { max: 3 }));
+    assert!(poh.verify().is_ok());
+}
+
+#[test]
+fn milestones_are_bound_into_the_next_entry() {
+    let config = PoHConfig::new(Sha256::new(), 32, 5, Some(4), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config.clone(), InitialSeed([14; 64]), None, vec![extensions::Extensions::Milestone]).unwrap();
+    poh.add_milestone("start".to_string()).unwrap();
+    assert_eq!(poh.add_milestone("start".to_string()), Err(PoHError::DuplicateMilestone("start".to_string())));
+    poh.append_data_entry(b"block".to_vec()).unwrap();
+    poh.add_milestone("block-1".to_string()).unwrap();
+    poh.append_data_entry(b"next".to_vec()).unwrap();
+
+    assert_eq!(poh.milestone("start"), Some(0));
+    assert_eq!(poh.milestone("block-1"), Some(1));
+    assert_eq!(poh.milestone("missing"), None);
+    assert_eq!(poh.state[2].milestones, vec!["block-1".to_string()]);
+    assert!(poh.verify().is_ok());
+
+    poh.state[2].milestones[0] = "block-2".to_string();
+    assert!(matches!(poh.verify(), Err(VerifyError::HashMismatch { index: 2, .. })));
+
+    let mut plain = PoHUsage::new(config, InitialSeed([14; 64]), None, vec![]).unwrap();
+    assert_eq!(plain.add_milestone("start".to_string()), Err(PoHError::ExtensionNotActive(extensions::Extensions::Milestone)));
+}
+
+#[test]
+fn ticks_bind_pending_milestones() {
+    let config = PoHConfig::new(Sha256::new(), 32, 5, Some(4), true, true, TickEntryType::Data);
+    let extensions = vec![extensions::Extensions::Milestone, extensions::Extensions::Breaker(2)];
+    let mut poh = PoHUsage::new(config, InitialSeed([86; 64]), None, extensions).unwrap();
+    poh.add_milestone("start".to_string()).unwrap();
+    let entry = poh.tick(poh.state[0].clone(), Some(b"block".to_vec())).unwrap();
+    assert_eq!(entry.milestones, vec!["start".to_string()]);
+    assert_eq!(entry.nonce, Some(0));
+
+    poh.add_milestone("block-1".to_string()).unwrap();
+    poh.tick(poh.state[1].clone(), Some(b"next".to_vec())).unwrap();
+    assert!(poh.state[2].breaker);
+    assert_eq!(poh.state[2].milestones, vec!["block-1".to_string()]);
+    assert_eq!(poh.state[3].nonce, Some(1));
+    assert_eq!(poh.verify(), Ok(()));
+
+    poh.state[2].milestones.clear();
+    assert!(matches!(poh.verify(), Err(VerifyError::HashMismatch { index: 2, .. })));
+}
+
+#[test]
+fn iter_ticks_matches_init() {
+    let config = PoHConfig::new(Sha256::new(), 32, 5, Some(6), true, true, TickEntryType::Data);
+    let extensions = vec![extensions::Extensions::Breaker(4)];
+    let poh = PoHUsage::new(config.clone(), InitialSeed([15; 64]), None, extensions).unwrap();
+    let lazy: Vec<PoHEntry> = poh.iter_ticks().collect();
+    assert_eq!(lazy.len(), 6);
+
+    let mut eager = poh.clone();
+    eager.init().unwrap();
+    assert_eq!(&eager.state[1..], &lazy[..]);
+    assert_eq!(eager.iter_ticks().count(), 0);
+
+    let config = PoHConfig::new(Sha256::new(), 32, 5, None, true, true, TickEntryType::Data);
+    let unbounded = PoHUsage::new(config, InitialSeed([15; 64]), None, vec![]).unwrap();
+    assert_eq!(unbounded.iter_ticks().take(50).last().unwrap().id, 50);
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn random_seeds_differ() {
+    assert_ne!(InitialSeed::random(), InitialSeed::random());
+
+    let mut seed = RandomSeed::new();
+    assert_ne!(seed.get_seed(), RandomSeed::new().get_seed());
+    seed.set_seed([1; 64]);
+    assert_eq!(seed.get_seed(), [1; 64]);
+}
+
+#[test]
+fn event_hash_from_data() {
+    let expected: [u8; 32] = Sha256::digest(b"event").into();
+    assert_eq!(EventHash::from_data(b"event").hash, expected);
+    assert_eq!(EventHash::from_event(&AppendedData::new(b"event".to_vec()), Sha256::new()).hash, expected);
+
+    let wide = EventHash::from_event(&b"event"[..], sha2::Sha512::new());
+    assert_eq!(wide.hash[..], sha2::Sha512::digest(b"event")[..32]);
+}
+
+#[test]
+fn init_rejects_unbounded_chains() {
+    let config = PoHConfig::new(Sha256::new(), 32, 5, None, true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed([16; 64]), None, vec![]).unwrap();
+    assert_eq!(poh.init(), Err(PoHError::Unbounded));
+    assert_eq!(poh.state.len(), 1);
+    assert_eq!(poh.iter_ticks().take(1500).count(), 1500);
+}
+
+#[test]
+fn from_hex_restores_entries() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(2), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed([9u8; 64]), None, vec![]).unwrap();
+    poh.init().unwrap();
+    let entry = &poh.state[1];
+    let restored = PoHEntry::from_hex(&entry.to_hex_string()).unwrap();
+    assert_eq!(restored.hash, entry.hash);
+    assert_eq!(restored.appended_data, None);
+
+    let restored = PoHEntry::from_hex_with_data(&entry.to_hex_string(), Some("cafe")).unwrap();
+    assert_eq!(restored.hash, entry.hash);
+    assert_eq!(restored.appended_data, Some(AppendedData::new(vec![0xca, 0xfe])));
+
+    assert!(PoHEntry::from_hex("not hex").is_err());
+    assert!(PoHEntry::from_hex_with_data(&entry.to_hex_string(), Some("0")).is_err());
+}
+
+#[test]
+fn resume_continues_a_restored_chain() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(6), true, true, TickEntryType::Data);
+    let mut full = PoHUsage::new(config.clone(), InitialSeed([4u8; 64]), None, vec![]).unwrap();
+    full.init().unwrap();
+
+    let mut restored = PoHUsage::from_state(config.clone(), full.state[..3].to_vec(), vec![]).unwrap();
+    restored.resume(2).unwrap();
+    assert_eq!(restored.state, full.state[..5]);
+    assert_eq!(restored.resume(3), Err(PoHError::MaxEntriesReached { max: 6 }));
+    assert_eq!(restored.state.len(), 5);
+    restored.resume(2).unwrap();
+    assert_eq!(restored.state, full.state);
+
+    let mut tampered = full.state.clone();
+    tampered[2].hash[0] ^= 1;
+    assert!(matches!(
+        PoHUsage::from_state(config.clone(), tampered, vec![]).err(),
+        Some(PoHError::Verify(VerifyError::HashMismatch { index: 2, .. }))
+    ));
+    assert_eq!(PoHUsage::from_state(config, vec![], vec![]).err(), Some(PoHError::Verify(VerifyError::EmptyChain)));
+    let small = PoHConfig::new(Sha256::new(), 32, 10, Some(4), true, true, TickEntryType::Data);
+    assert_eq!(PoHUsage::from_state(small, full.state.clone(), vec![]).err(), Some(PoHError::MaxEntriesReached { max: 4 }));
+}
+
+#[test]
+fn adopted_chains_need_consecutive_ids() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, None, true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config.clone(), InitialSeed([84u8; 64]), None, vec![]).unwrap();
+    poh.resume(2).unwrap();
+
+    let mut gaps = poh.state.clone();
+    gaps[1].id = 7;
+    gaps[2].id = u64::MAX;
+    let malformed = VerifyError::Malformed { index: 1, reason: "id does not follow its predecessor" };
+    assert_eq!(PoHUsage::from_state(config.clone(), gaps, vec![]).err(), Some(PoHError::Verify(malformed)));
+
+    let mut last = poh.state[1..].to_vec();
+    last[0].id = u64::MAX - 1;
+    last[1].id = u64::MAX;
+    let mut adopted = PoHUsage::from_state(config, last, vec![]).unwrap();
+    assert_eq!(adopted.resume(1), Err(PoHError::IdsExhausted));
+    assert_eq!(adopted.append_data_entry(b"tx".to_vec()).err(), Some(PoHError::IdsExhausted));
+    assert_eq!(adopted.iter_ticks().next(), None);
+    assert_eq!(adopted.len(), 2);
+}
+
+#[test]
+fn indexed_access_helpers() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(3), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed([6u8; 64]), None, vec![]).unwrap();
+    assert_eq!(poh.len(), 1);
+    assert_eq!(poh.first(), poh.last());
+    poh.init().unwrap();
+    assert_eq!(poh.len(), 4);
+    assert!(!poh.is_empty());
+    assert_eq!(poh.first(), Some(&poh.state[0]));
+    assert_eq!(poh.last(), Some(&poh.state[3]));
+    assert_eq!(poh.entry(2), Some(&poh.state[2]));
+    assert_eq!(poh.entry(4), None);
+}
+
+#[test]
+fn event_hash_types_fix_entry_length() {
+    let variants = [
+        (TickEntryType::EventHash28, 28),
+        (TickEntryType::EventHash32, 32),
+        (TickEntryType::EventHash48, 48),
+        (TickEntryType::EventHash64, 64),
+    ];
+    for (entry_type, len) in variants {
+        let config = PoHConfig::new(sha2::Sha512::new(), 16, 10, Some(2), true, true, entry_type);
+        assert_eq!(config.hash_len(), len);
+        let mut poh = PoHUsage::new(config, InitialSeed([8u8; 64]), None, vec![]).unwrap();
+        poh.init().unwrap();
+        poh.truncate(2);
+        poh.tick(poh.state[1].clone(), Some(b"event".to_vec())).unwrap();
+        assert!(poh.state.iter().all(|entry| entry.hash.len() == len));
+        assert!(poh.verify().is_ok());
+
+        let config = PoHConfig::new(Sha256::new(), 16, 10, Some(2), true, true, entry_type);
+        let result = PoHUsage::new(config, InitialSeed([8u8; 64]), None, vec![]);
+        if len > 32 {
+            assert_eq!(result.err(), Some(PoHError::OutputSizeMismatch { configured: len, hasher: 32 }));
+        } else {
+            assert!(result.is_ok());
+        }
+    }
+}
+
+/// Builds, extends and verifies a chain with `hasher`, exercising every path that
+/// is generic over the digest.
+#[cfg(test)]
+fn exercise_hasher<D: Digest + Clone>(hasher: D) {
+    let config = PoHConfig::builder(hasher.clone()).tick_interval(20).max_entries(Some(6)).build().unwrap();
+    assert_eq!(config.hash_len(), <D as Digest>::output_size());
+    let mut poh = PoHUsage::new(config.clone(), InitialSeed([15u8; 64]), Some(b"genesis".to_vec()), vec![extensions::Extensions::MerkleTree]).unwrap();
+    poh.append_data_entry(b"event".to_vec()).unwrap();
+    poh.resume(4).unwrap();
+    assert_eq!(poh.len(), 6);
+    assert_eq!(poh.hashes_per_entry(), 20);
+    assert!(poh.state.iter().all(|entry| entry.hash.len() == config.hash_len()));
+    assert!(poh.verify().is_ok());
+    assert!(poh.iter_ticks().next().is_some());
+
+    let decoded = PoHUsage::from_bytes(&poh.to_bytes().unwrap(), config.clone()).unwrap();
+    assert_eq!(decoded.state, poh.state);
+    let root = poh.merkle_root().unwrap();
+    let proof = poh.merkle_proof(1).unwrap();
+    assert!(verify_merkle_proof(hasher, &root, &poh.state[1].hash, 1, poh.len(), &proof));
+
+    let truncated = PoHConfig::builder(config.hasher.clone()).output_size(16).build().unwrap();
+    let mut poh = PoHUsage::new(truncated, InitialSeed([15u8; 64]), None, vec![]).unwrap();
+    poh.resume(2).unwrap();
+    assert!(poh.state.iter().all(|entry| entry.hash.len() == 16));
+    assert!(poh.verify().is_ok());
+}
+
+#[test]
+fn generic_over_sha256() {
+    exercise_hasher(Sha256::new());
+}
+
+#[test]
+fn generic_over_sha512() {
+    exercise_hasher(sha2::Sha512::new());
+}
+
+#[cfg(feature = "blake3")]
+#[test]
+fn generic_over_blake3() {
+    exercise_hasher(blake3::Hasher::new());
+}
+
+#[test]
+fn max_data_len_limits_appended_data() {
+    let seed = InitialSeed([16u8; 64]);
+    let config = PoHConfig::builder(Sha256::new()).tick_interval(10).max_entries(Some(4)).max_data_len(Some(4)).build().unwrap();
+    assert_eq!(
+        PoHUsage::new(config.clone(), seed.clone(), Some(vec![0; 5]), vec![]).err(),
+        Some(PoHError::DataTooLarge { len: 5, max: 4 })
+    );
+    let mut poh = PoHUsage::new(config, seed, Some(vec![0; 4]), vec![]).unwrap();
+    assert_eq!(poh.state[0].appended_data.as_ref().map(AppendedData::len), Some(4));
+    assert_eq!(poh.append_data_entry(vec![1; 5]).err(), Some(PoHError::DataTooLarge { len: 5, max: 4 }));
+    assert_eq!(poh.len(), 1);
+    let entry = poh.append_data_entry(vec![1; 4]).unwrap();
+    assert!(!entry.appended_data.as_ref().unwrap().is_empty());
+}
+
+#[test]
+fn fork_branches_independently() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(6), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed([19u8; 64]), None, vec![extensions::Extensions::Milestone]).unwrap();
+    poh.resume(2).unwrap();
+    poh.add_milestone("two".to_string()).unwrap();
+    poh.resume(2).unwrap();
+
+    let mut fork = poh.fork(2).unwrap();
+    assert_ne!(fork.id, poh.id);
+    assert_eq!(fork.state, poh.state[..3]);
+    assert_eq!(fork.milestone("two"), Some(2));
+    fork.append_data_entry(b"alternate".to_vec()).unwrap();
+    assert_eq!(fork.state[3].milestones, vec!["two".to_string()]);
+    assert_eq!(fork.state[3].hash.len(), poh.state[3].hash.len());
+    assert_ne!(fork.state[3], poh.state[3]);
+    assert!(fork.verify().is_ok());
+    assert!(poh.verify().is_ok());
+
+    assert_eq!(poh.fork(5).err(), Some(PoHError::IndexOutOfRange { index: 5, len: 5 }));
+}
+
+#[test]
+fn instances_get_unique_ids() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(2), true, true, TickEntryType::Data);
+    let ids: Vec<u64> = (0..4)
+        .map(|_| PoHUsage::new(config.clone(), InitialSeed([20u8; 64]), None, vec![]).unwrap().get_id())
+        .collect();
+    assert!(ids.iter().all(|&id| id != 0));
+    assert!(ids.windows(2).all(|pair| pair[0] != pair[1]));
+
+    let poh = PoHUsage::new(config, InitialSeed([20u8; 64]), None, vec![]).unwrap().with_id(42);
+    assert_eq!(poh.get_id(), 42);
+}
+
+#[test]
+fn entries_order_by_chain_position() {
+    use alloc::collections::BTreeSet;
+
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(5), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed([21u8; 64]), None, vec![]).unwrap();
+    poh.init().unwrap();
+    let shuffled: BTreeSet<PoHEntry> = poh.state.iter().rev().cloned().collect();
+    assert!(shuffled.iter().eq(poh.state.iter()));
+    assert!(poh.state.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[test]
+fn generate_builds_a_sha256_chain() {
+    let entries = generate([23u8; 64], 10, 5);
+    assert_eq!(entries.len(), 6);
+
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(5), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed([23u8; 64]), None, vec![]).unwrap();
+    poh.init().unwrap();
+    assert_eq!(entries, poh.state);
+}
+
+#[test]
+fn reset_matches_a_fresh_instance() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(4), true, true, TickEntryType::Data);
+    let extensions = vec![extensions::Extensions::Milestone];
+    let mut poh = PoHUsage::new(config.clone(), InitialSeed([25u8; 64]), None, extensions.clone()).unwrap();
+    poh.add_milestone("start".to_string()).unwrap();
+    poh.init().unwrap();
+    poh.prune_before(2).unwrap();
+    let id = poh.get_id();
+
+    poh.reset(InitialSeed([26u8; 64]), Some(b"again".to_vec())).unwrap();
+    let fresh = PoHUsage::new(config.clone(), InitialSeed([26u8; 64]), Some(b"again".to_vec()), extensions).unwrap();
+    assert_eq!(poh.state, fresh.state);
+    assert_eq!(poh.milestones, fresh.milestones);
+    assert_eq!(poh.pruned_commitment(), None);
+    #[cfg(feature = "std")]
+    assert_eq!(poh.last_run_stats(), None);
+    assert_eq!(poh.get_id(), id);
+    poh.init().unwrap();
+    assert!(poh.verify().is_ok());
+
+    let mut strict = PoHUsage::new(config, InitialSeed([25u8; 64]), None, vec![]).unwrap();
+    strict.config.allow_data_entries = false;
+    let before = strict.state.clone();
+    assert_eq!(strict.reset(InitialSeed([26u8; 64]), Some(vec![1])), Err(PoHError::DataNotAllowed));
+    assert_eq!(strict.state, before);
+}
+
+#[test]
+fn seeds_convert_from_bytes() {
+    let bytes = [28u8; 64];
+    assert_eq!(InitialSeed::from(bytes), InitialSeed(bytes));
+    assert_eq!(InitialSeed::try_from(&bytes[..]), Ok(InitialSeed(bytes)));
+    assert_eq!(InitialSeed::try_from(&bytes[..63]), Err(PoHError::InvalidSeedLength { len: 63 }));
+    assert_eq!(InitialSeed::try_from(&[0u8; 65][..]), Err(PoHError::InvalidSeedLength { len: 65 }));
+    assert_eq!(InitialSeed(bytes).as_bytes(), &bytes);
+}
+
+#[cfg(feature = "pbkdf2")]
+#[test]
+fn passphrase_seeds_match_known_answer() {
+    // PBKDF2-HMAC-SHA512, as computed by Python's hashlib.pbkdf2_hmac.
+    let seed = InitialSeed::from_passphrase_with_rounds("correct horse battery staple", b"poh-yugen", 1000);
+    assert_eq!(
+        hex::encode(seed.as_bytes()),
+        "4c0be1467aef6096b1759575e72c62c78c8d4ce342b3e4cf109750673f33d993\
+         d69dc4e80d9fccc961c222a7357a8cab85c721de30f365100d6f338156e41c3a"
+    );
+    assert_ne!(InitialSeed::from_passphrase_with_rounds("correct horse battery staple", b"other salt", 1000), seed);
+}
+
+#[test]
+fn data_mix_modes_bind_data_differently() {
+    let seed = InitialSeed([29u8; 64]);
+    let mut tips = Vec::new();
+    for mode in [DataMixMode::Prefix, DataMixMode::Suffix, DataMixMode::Interleaved] {
+        let config = PoHConfig::builder(Sha256::new()).tick_interval(3).max_entries(Some(2)).data_mix(mode).build().unwrap();
+        let mut poh = PoHUsage::new(config.clone(), seed.clone(), Some(b"init".to_vec()), vec![]).unwrap();
+        poh.append_data_entry(b"event".to_vec()).unwrap();
+        poh.resume(1).unwrap();
+        assert!(poh.verify().is_ok());
+
+        let mut other = config;
+        other.data_mix = if mode == DataMixMode::Suffix { DataMixMode::Prefix } else { DataMixMode::Suffix };
+        assert!(PoHUsage::from_state(other, poh.state.clone(), vec![]).is_err());
+        tips.push(poh.state[1].hash.clone());
+    }
+    assert!(tips[0] != tips[1] && tips[1] != tips[2] && tips[0] != tips[2]);
+
+    // Suffix is the default and the original behaviour: data follows the final
+    // round, followed by the entry's nonce.
+    let config = PoHConfig::new(Sha256::new(), 32, 1, Some(1), true, true, TickEntryType::Data);
+    assert_eq!(config.data_mix, DataMixMode::Suffix);
+    let mut poh = PoHUsage::new(config, seed, None, vec![]).unwrap();
+    poh.append_data_entry(b"event".to_vec()).unwrap();
+    let mut hasher = Sha256::new();
+    hasher.update(&poh.state[0].hash);
+    hasher.update(b"event");
+    hasher.update(NONCE_TAG);
+    hasher.update(0u64.to_le_bytes());
+    assert_eq!(poh.state[1].hash, hasher.finalize().to_vec());
+}
+
+#[test]
+fn append_data_batch_is_all_or_nothing() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(4), true, true, TickEntryType::UTF8String);
+    let mut poh = PoHUsage::new(config, InitialSeed([30u8; 64]), None, vec![extensions::Extensions::Breaker(3)]).unwrap();
+
+    let added = poh.append_data_batch(vec![b"a".to_vec(), b"b".to_vec()]).unwrap();
+    assert_eq!(added.len(), 2);
+    assert_eq!(added[1].appended_data, Some(AppendedData::new(b"b".to_vec())));
+
+    let before = poh.state.clone();
+    assert_eq!(poh.append_data_batch(vec![b"c".to_vec(), vec![0xff]]).err(), Some(PoHError::EntryTypeMismatch(TickEntryType::UTF8String)));
+    assert_eq!(poh.state, before);
+    // The breaker before "c" fits, "d" does not.
+    assert_eq!(poh.append_data_batch(vec![b"c".to_vec(), b"d".to_vec()]).err(), Some(PoHError::MaxEntriesReached { max: 4 }));
+    assert_eq!(poh.state, before);
+
+    let added = poh.append_data_batch(vec![b"c".to_vec()]).unwrap();
+    assert!(added[0].breaker && added[1].appended_data.is_some());
+    assert!(poh.verify().is_ok());
+}
+
+#[test]
+fn extend_from_data_appends_each_item() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(3), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config.clone(), InitialSeed([31u8; 64]), None, vec![]).unwrap();
+    poh.extend_from_data((0u8..2).map(|event| vec![event])).unwrap();
+    let mut expected = PoHUsage::new(config, InitialSeed([31u8; 64]), None, vec![]).unwrap();
+    expected.append_data_entry(vec![0]).unwrap();
+    expected.append_data_entry(vec![1]).unwrap();
+    assert_eq!(poh.state, expected.state);
+
+    assert_eq!(poh.extend_from_data((2u8..10).map(|event| vec![event])), Err(PoHError::MaxEntriesReached { max: 3 }));
+    assert_eq!(poh.len(), 4);
+    assert!(poh.verify().is_ok());
+}
+
+#[test]
+fn hashes_export_in_chain_order() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(2), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed([32u8; 64]), None, vec![]).unwrap();
+    poh.init().unwrap();
+    let hashes = poh.hashes();
+    assert_eq!(hashes.len(), 3);
+    assert!(hashes.iter().zip(&poh.state).all(|(hash, entry)| *hash == entry.hash));
+    let fixed = poh.hashes_fixed().unwrap();
+    assert!(fixed.iter().zip(&hashes).all(|(fixed, hash)| fixed[..] == hash[..]));
+
+    let config = PoHConfig::new(Sha256::new(), 20, 10, Some(2), true, true, TickEntryType::Data);
+    let short = PoHUsage::new(config, InitialSeed([32u8; 64]), None, vec![]).unwrap();
+    assert_eq!(short.hashes_fixed(), Err(PoHError::HashLengthMismatch { index: 0, len: 20, expected: 32 }));
+}
+
+#[test]
+fn new_normalizes_extensions() {
+    use extensions::Extensions;
+
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(2), true, true, TickEntryType::Data);
+    let poh = PoHUsage::new(config.clone(), InitialSeed([33u8; 64]), None, vec![Extensions::MerkleTree, Extensions::MerkleTree]).unwrap();
+    assert_eq!(poh.extensions, vec![Extensions::MerkleTree]);
+    assert!(poh.has_extension(Extensions::MerkleTree));
+    assert!(!poh.has_extension(Extensions::Milestone));
+
+    let conflicting = vec![Extensions::Timestamp, Extensions::TimestampRFC3339];
+    assert_eq!(
+        PoHUsage::new(config, InitialSeed([33u8; 64]), None, conflicting).err(),
+        Some(PoHError::ConflictingExtensions(Extensions::Timestamp, Extensions::TimestampRFC3339))
+    );
+}
+
+#[test]
+fn total_work_sums_entry_rounds() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(4), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed([34u8; 64]), None, vec![]).unwrap();
+    assert_eq!(poh.total_work(), 1);
+    poh.init().unwrap();
+    assert_eq!(poh.state[0].work(10), 1);
+    assert_eq!(poh.state[3].work(10), 10);
+    assert_eq!(poh.total_work(), 41);
+}
+
+#[test]
+fn tick_with_interval_replays_its_own_rounds() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(4), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed([35u8; 64]), None, vec![]).unwrap();
+    poh.tick_with_interval(3).unwrap();
+    poh.resume(1).unwrap();
+    assert_eq!(poh.tick_with_interval(25).unwrap().interval, 25);
+    assert_eq!(poh.tick_with_interval(0).err(), Some(PoHError::InvalidConfig("tick_interval must be greater than zero")));
+    assert_eq!(poh.state.iter().map(|entry| entry.interval).collect::<Vec<_>>(), vec![0, 3, 0, 25]);
+    assert_eq!(poh.total_work(), 1 + 3 + 10 + 25);
+    assert!(poh.verify().is_ok());
+
+    let restored = PoHUsage::from_bytes(&poh.to_bytes().unwrap(), poh.config.clone()).unwrap();
+    assert_eq!(restored.state, poh.state);
+
+    poh.state[1].interval = 4;
+    assert!(matches!(poh.verify(), Err(VerifyError::HashMismatch { index: 1, .. })));
+}
+
+#[test]
+fn same_genesis_detects_shared_origins() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(4), true, true, TickEntryType::Data);
+    let mut a = PoHUsage::new(config.clone(), InitialSeed([36u8; 64]), Some(b"init".to_vec()), vec![]).unwrap();
+    let b = PoHUsage::new(config.clone(), InitialSeed([36u8; 64]), Some(b"init".to_vec()), vec![]).unwrap();
+    a.resume(2).unwrap();
+    assert!(a.same_genesis(&b));
+    assert!(a.same_genesis(&a.fork(1).unwrap()));
+
+    let other_seed = PoHUsage::new(config.clone(), InitialSeed([37u8; 64]), Some(b"init".to_vec()), vec![]).unwrap();
+    let other_data = PoHUsage::new(config, InitialSeed([36u8; 64]), None, vec![]).unwrap();
+    assert!(!a.same_genesis(&other_seed));
+    assert!(!a.same_genesis(&other_data));
+}
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn seeds_zeroize() {
+    use zeroize::Zeroize;
+
+    let mut seed = InitialSeed([40u8; 64]);
+    seed.zeroize();
+    assert_eq!(seed.as_bytes(), &[0u8; 64]);
+}
+
+#[test]
+fn lanes_multiplex_streams() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(6), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed([42u8; 64]), None, vec![]).unwrap();
+    poh.append_to_lane(1, b"tx-1".to_vec()).unwrap();
+    poh.append_to_lane(2, b"meta".to_vec()).unwrap();
+    poh.append_data_entry(b"plain".to_vec()).unwrap();
+    poh.append_to_lane(1, b"tx-2".to_vec()).unwrap();
+    assert!(poh.verify().is_ok());
+
+    let lane = |lane| poh.entries_for_lane(lane).map(|entry| entry.id).collect::<Vec<_>>();
+    assert_eq!(lane(1), vec![1, 4]);
+    assert_eq!(lane(2), vec![2]);
+    assert_eq!(lane(0), vec![3]);
+
+    let restored = PoHUsage::from_bytes(&poh.to_bytes().unwrap(), poh.config.clone()).unwrap();
+    assert_eq!(restored.state, poh.state);
+
+    poh.state[2].appended_data = Some(AppendedData::with_lane(1, b"meta".to_vec()));
+    assert!(matches!(poh.verify(), Err(VerifyError::HashMismatch { index: 2, .. })));
+}
+
+#[test]
+fn truncate_drops_the_tail() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(6), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed([44u8; 64]), None, vec![extensions::Extensions::Milestone]).unwrap();
+    poh.resume(2).unwrap();
+    poh.add_milestone("two".to_string()).unwrap();
+    poh.resume(2).unwrap();
+    poh.add_milestone("four".to_string()).unwrap();
+    poh.resume(1).unwrap();
+    let original = poh.clone();
+
+    poh.truncate(10);
+    assert_eq!((&poh.state, poh.milestones()), (&original.state, original.milestones()));
+    poh.truncate(3);
+    assert_eq!(poh.state, original.state[..3]);
+    assert_eq!(poh.milestones(), &[("two".to_string(), 2)]);
+    assert!(poh.verify().is_ok());
+
+    // The milestone on the new tip is bound again by the next entry.
+    poh.resume(1).unwrap();
+    assert_eq!(poh.state[3], original.state[3]);
+    poh.truncate(0);
+    assert_eq!(poh.len(), 1);
+    assert!(poh.milestones().is_empty());
+}
+
+#[test]
+fn entries_are_found_by_hash() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(4), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed([45u8; 64]), None, vec![]).unwrap();
+    poh.init().unwrap();
+    let hash = poh.state[3].hash.clone();
+    assert_eq!(poh.index_of(&hash), Some(3));
+    assert_eq!(poh.find(&hash), Some(&poh.state[3]));
+    assert_eq!(poh.index_of(&[0; 32]), None);
+    assert_eq!(poh.find(&hash[..16]), None);
+}
+
+#[test]
+fn diverges_at_finds_the_fork_point() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(6), true, true, TickEntryType::Data);
+    let mut a = PoHUsage::new(config, InitialSeed([49u8; 64]), None, vec![]).unwrap();
+    a.resume(2).unwrap();
+    let mut b = a.fork(2).unwrap();
+    assert_eq!(a.diverges_at(&b), None);
+    assert!(!a.is_strict_prefix_of(&b));
+
+    b.resume(1).unwrap();
+    assert_eq!(a.diverges_at(&b), None);
+    assert!(a.is_strict_prefix_of(&b));
+    assert!(!b.is_strict_prefix_of(&a));
+
+    a.append_data_entry(b"a".to_vec()).unwrap();
+    b.append_data_entry(b"b".to_vec()).unwrap();
+    assert_eq!(a.diverges_at(&b), Some(3));
+    assert_eq!(b.diverges_at(&a), Some(3));
+    assert!(!a.is_strict_prefix_of(&b));
+}
+
+#[test]
+fn nonces_tell_identical_payloads_apart() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(6), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed([51u8; 64]), None, vec![]).unwrap();
+    poh.append_data_entry(b"same".to_vec()).unwrap();
+    poh.resume(1).unwrap();
+    poh.append_data_entry(b"same".to_vec()).unwrap();
+    poh.append_data_with_nonce(b"same".to_vec(), 40).unwrap();
+    poh.append_data_batch(vec![b"same".to_vec()]).unwrap();
+    let nonces: Vec<_> = poh.state.iter().map(|entry| entry.nonce).collect();
+    assert_eq!(nonces, vec![None, Some(0), None, Some(1), Some(40), Some(41)]);
+    assert!(poh.verify().is_ok());
+
+    let restored = PoHUsage::from_bytes(&poh.to_bytes().unwrap(), poh.config.clone()).unwrap();
+    assert_eq!(restored.state, poh.state);
+
+    poh.state[3].nonce = Some(2);
+    assert!(matches!(poh.verify(), Err(VerifyError::HashMismatch { index: 3, .. })));
+}
+
+#[test]
+fn nonces_continue_after_eviction() {
+    let config = PoHConfig::builder(Sha256::new()).tick_interval(10).window(Some(2)).build().unwrap();
+    let mut poh = PoHUsage::new(config.clone(), InitialSeed([87u8; 64]), None, vec![]).unwrap();
+    poh.append_data_entry(b"same".to_vec()).unwrap();
+    poh.resume(3).unwrap();
+    assert!(poh.state.iter().all(|entry| entry.nonce.is_none()));
+    assert_eq!(poh.snapshot().last_nonce, Some(0));
+
+    let mut restored = PoHUsage::from_bytes(&poh.to_bytes().unwrap(), config.clone()).unwrap();
+    let mut resumed = PoHUsage::from_snapshot(poh.snapshot(), config, vec![]).unwrap();
+    for poh in [&mut poh, &mut restored, &mut resumed] {
+        assert_eq!(poh.append_data_entry(b"same".to_vec()).unwrap().nonce, Some(1));
+    }
+    assert_eq!(restored.last(), poh.last());
+    assert_eq!(resumed.last(), poh.last());
+}
+
+#[test]
+fn endianness_orders_hashed_integers() {
+    assert_eq!(Endianness::Little.u64_bytes(1), [1, 0, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(Endianness::Big.u64_bytes(1), [0, 0, 0, 0, 0, 0, 0, 1]);
+
+    let mut tips = Vec::new();
+    for endianness in [Endianness::Little, Endianness::Big] {
+        let config = PoHConfig::builder(Sha256::new()).tick_interval(1).max_entries(Some(1)).endianness(endianness).build().unwrap();
+        let mut poh = PoHUsage::new(config, InitialSeed([53u8; 64]), None, vec![]).unwrap();
+        poh.append_data_with_nonce(b"event".to_vec(), 1).unwrap();
+        assert!(poh.verify().is_ok());
+
+        let mut hasher = Sha256::new();
+        hasher.update(&poh.state[0].hash);
+        hasher.update(b"event");
+        hasher.update(NONCE_TAG);
+        hasher.update(endianness.u64_bytes(1));
+        assert_eq!(poh.state[1].hash, hasher.finalize().to_vec());
+        tips.push(poh.state[1].hash.clone());
+    }
+    assert_ne!(tips[0], tips[1]);
+}
+
+#[test]
+fn chains_iterate_over_entries() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(3), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed([54u8; 64]), None, vec![]).unwrap();
+    poh.init().unwrap();
+    let mut ids = Vec::new();
+    for entry in &poh {
+        ids.push(entry.id);
+    }
+    assert_eq!(ids, vec![0, 1, 2, 3]);
+    let state = poh.state.clone();
+    assert_eq!(poh.into_iter().collect::<Vec<_>>(), state);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn new_unique_rejects_reused_seeds() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(2), true, true, TickEntryType::Data);
+    let seed = InitialSeed([56u8; 64]);
+    let first = PoHUsage::new_unique(config.clone(), seed.clone(), None, vec![]).unwrap();
+    assert_eq!(PoHUsage::new_unique(config.clone(), seed.clone(), None, vec![]).err(), Some(PoHError::SeedReused));
+    assert!(PoHUsage::new_unique(config.clone(), seed.clone(), Some(b"other".to_vec()), vec![]).is_ok());
+
+    let again = PoHUsage::new(config.clone(), seed.clone(), None, vec![]).unwrap();
+    assert_eq!(again.fingerprint(), first.fingerprint());
+    let slower = PoHUsage::new(PoHConfig { tick_interval: 11, ..config }, seed, None, vec![]).unwrap();
+    assert_ne!(slower.fingerprint(), first.fingerprint());
+}
+
+#[test]
+fn tip_follows_the_newest_entry() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(2), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed([60u8; 64]), None, vec![]).unwrap();
+    assert_eq!(poh.tip(), Some(poh.genesis_hash()));
+    poh.resume(1).unwrap();
+    assert_eq!(poh.tip(), Some(poh.state[1].hash.as_slice()));
+    assert_eq!(poh.tip_hex(), Some(hex::encode(&poh.state[1].hash)));
+
+    poh.state.clear();
+    assert_eq!(poh.tip(), None);
+    assert_eq!(poh.tip_hex(), None);
+}
+
+#[test]
+fn tick_n_appends_exactly_n_entries() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(5), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config.clone(), InitialSeed([61u8; 64]), None, vec![]).unwrap();
+    let added = poh.tick_n(3).unwrap().to_vec();
+    assert_eq!(added.len(), 3);
+    assert!(added.iter().all(|entry| entry.appended_data.is_none()));
+    assert_eq!(added, poh.state[1..]);
+    assert!(poh.tick_n(0).unwrap().is_empty());
+    assert_eq!(poh.tick_n(3), Err(PoHError::MaxEntriesReached { max: 5 }));
+    assert_eq!(poh.len(), 4);
+
+    let data_only = PoHConfig { allow_empty_entries: false, ..config };
+    let mut poh = PoHUsage::new(data_only, InitialSeed([61u8; 64]), Some(b"genesis".to_vec()), vec![]).unwrap();
+    assert_eq!(poh.tick_n(1), Err(PoHError::EmptyNotAllowed));
+}
+
+#[test]
+fn events_are_folded_in_as_data_entries() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(4), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config.clone(), InitialSeed([63u8; 64]), None, vec![]).unwrap();
+    let entry = poh.append_event(&b"transfer"[..]).unwrap().clone();
+    let event = EventHash::from_event(&b"transfer"[..], Sha256::new());
+    assert_eq!(entry.event, Some(event));
+    assert_eq!(entry.appended_data, Some(AppendedData::new(event.hash.to_vec())));
+    assert_eq!(entry.nonce, Some(0));
+
+    poh.append_event(&AppendedData::new(b"transfer".to_vec())).unwrap();
+    assert_eq!(poh.state[2].event, Some(event));
+    assert_ne!(poh.state[2].hash, poh.state[1].hash);
+    assert_eq!(poh.verify(), Ok(()));
+    assert_eq!(PoHUsage::from_bytes(&poh.to_bytes().unwrap(), config).unwrap().get_state(), poh.get_state());
+}
+
+#[test]
+fn work_limits_are_checked() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(4), true, true, TickEntryType::Data);
+    assert_eq!(config.planned_work(), Ok(41));
+    assert_eq!(PoHConfig { max_entries: None, ..config.clone() }.planned_work(), Err(PoHError::Unbounded));
+
+    let huge = PoHConfig { tick_interval: u64::MAX, max_entries: Some(2), ..config.clone() };
+    assert_eq!(huge.planned_work(), Err(PoHError::TooLarge));
+    assert_eq!(huge.validate(), Err(PoHError::TooLarge));
+    assert!(PoHConfig { max_entries: None, ..huge }.validate().is_ok());
+    let edge = PoHConfig { tick_interval: u64::MAX - 1, max_entries: Some(1), ..config.clone() };
+    assert_eq!(edge.planned_work(), Ok(u64::MAX));
+
+    let mut poh = PoHUsage::new(config, InitialSeed([64u8; 64]), None, vec![]).unwrap();
+    assert_eq!(poh.resume(usize::MAX), Err(PoHError::MaxEntriesReached { max: 4 }));
+    poh.resume(1).unwrap();
+    poh.state[1].interval = u64::MAX;
+    assert_eq!(poh.total_work(), u64::MAX);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn extensions_can_change_mid_chain() {
+    use extensions::Extensions;
+
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(8), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config.clone(), InitialSeed([65u8; 64]), None, vec![]).unwrap();
+    poh.resume(2).unwrap();
+    poh.set_extensions(vec![Extensions::Timestamp, Extensions::Breaker(4), Extensions::Timestamp]).unwrap();
+    assert_eq!(poh.extensions(), [Extensions::Timestamp, Extensions::Breaker(4)]);
+    poh.resume(3).unwrap();
+    assert_eq!(poh.set_extensions(vec![Extensions::Timestamp, Extensions::TimestampRFC3339]), Err(PoHError::ConflictingExtensions(Extensions::Timestamp, Extensions::TimestampRFC3339)));
+    poh.set_extensions(vec![]).unwrap();
+    poh.resume(1).unwrap();
+
+    assert!(poh.state[..3].iter().all(|entry| entry.timestamp.is_none()));
+    assert!(poh.state[3..6].iter().all(|entry| entry.timestamp.is_some()));
+    assert!(poh.state[4].breaker);
+    assert_eq!(poh.state[6].timestamp, None);
+    assert_eq!(poh.verify(), Ok(()));
+    assert!(PoHUsage::from_state(config, poh.state.clone(), vec![]).is_ok());
+}
+
+#[test]
+fn chains_behave_like_entry_slices() {
+    fn count_data(entries: &[PoHEntry]) -> usize {
+        entries.iter().filter(|entry| entry.appended_data.is_some()).count()
+    }
+
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(4), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed([67u8; 64]), None, vec![]).unwrap();
+    poh.append_data_entry(b"tx".to_vec()).unwrap();
+    poh.resume(2).unwrap();
+
+    assert_eq!(count_data(&poh), 1);
+    assert_eq!(count_data(poh.as_ref()), 1);
+    assert_eq!(poh[1].appended_data, Some(AppendedData::new(b"tx".to_vec())));
+    assert_eq!(poh.iter().count(), 4);
+    assert_eq!(poh[1..].len(), 3);
+}
+
+#[test]
+fn deterministic_ids_come_from_the_genesis_hash() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(2), true, true, TickEntryType::Data);
+    let a = PoHUsage::new(config.clone(), InitialSeed([68u8; 64]), None, vec![]).unwrap();
+    let b = PoHUsage::new(config.clone(), InitialSeed([68u8; 64]), None, vec![]).unwrap().with_deterministic_id();
+    assert_ne!(a.get_id(), b.get_id());
+    assert_eq!(a.deterministic_id(), b.get_id());
+    assert_eq!(a.deterministic_id(), u64::from_le_bytes(a.genesis_hash()[..8].try_into().unwrap()));
+
+    let short = PoHUsage::new(PoHConfig { output_size: 4, ..config }, InitialSeed([68u8; 64]), None, vec![]).unwrap();
+    assert_eq!(short.deterministic_id(), u64::from(u32::from_le_bytes(short.genesis_hash().try_into().unwrap())));
+}
+
+#[test]
+fn data_and_empty_entries_are_counted() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(5), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed([70u8; 64]), Some(b"genesis".to_vec()), vec![]).unwrap();
+    poh.resume(1).unwrap();
+    poh.append_data_entry(b"a".to_vec()).unwrap();
+    poh.resume(2).unwrap();
+    poh.append_data_entry(b"b".to_vec()).unwrap();
+
+    assert_eq!(poh.count_data_entries(), 3);
+    assert_eq!(poh.count_empty_entries(), 3);
+    assert_eq!(poh.data_entries().map(|(index, _)| index).collect::<Vec<_>>(), vec![0, 2, 5]);
+    assert!(poh.data_entries().all(|(index, entry)| poh.state[index] == *entry));
+}
+
+#[test]
+fn data_entries_are_spaced_apart() {
+    let config = PoHConfig::builder(Sha256::new()).tick_interval(10).max_entries(Some(8)).min_interval_between_data(35).build().unwrap();
+    let mut poh = PoHUsage::new(config.clone(), InitialSeed([71u8; 64]), None, vec![]).unwrap();
+    poh.append_data_entry(b"first".to_vec()).unwrap();
+    assert_eq!(poh.state[1].interval, 0);
+    poh.append_data_entry(b"second".to_vec()).unwrap();
+    assert_eq!(poh.state[2].interval, 35);
+    poh.resume(2).unwrap();
+    poh.append_data_entry(b"third".to_vec()).unwrap();
+    assert_eq!(poh.state[5].interval, 15);
+    poh.resume(3).unwrap();
+    assert_eq!(poh.verify(), Ok(()));
+    assert_eq!(poh.append_data_entry(b"late".to_vec()).map(|entry| entry.interval), Err(PoHError::MaxEntriesReached { max: 8 }));
+
+    let mut spaced = PoHUsage::new(config, InitialSeed([71u8; 64]), None, vec![]).unwrap();
+    spaced.append_data_batch(vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec()]).unwrap();
+    assert_eq!(spaced.state.iter().map(|entry| entry.interval).collect::<Vec<_>>(), vec![0, 0, 35, 35]);
+    assert_ne!(spaced.get_config().config_hash(), PoHConfig::new(Sha256::new(), 32, 10, Some(8), true, true, TickEntryType::Data).config_hash());
+}
+
+#[test]
+fn spacing_survives_ticks_and_eviction() {
+    let config = PoHConfig::builder(Sha256::new()).tick_interval(10).min_interval_between_data(100).build().unwrap();
+    let mut poh = PoHUsage::new(config.clone(), InitialSeed([85u8; 64]), None, vec![]).unwrap();
+    poh.append_data_entry(b"first".to_vec()).unwrap();
+    let tip = poh.state[1].clone();
+    assert_eq!(poh.tick(tip.clone(), Some(b"second".to_vec())).unwrap().interval, 100);
+    assert_eq!(poh.tick(tip, None).err(), Some(PoHError::NotTip));
+    assert_eq!(poh.verify(), Ok(()));
+
+    let windowed = PoHConfig { min_interval_between_data: 1000, window: Some(2), ..config };
+    let mut poh = PoHUsage::new(windowed.clone(), InitialSeed([85u8; 64]), None, vec![]).unwrap();
+    poh.append_data_entry(b"first".to_vec()).unwrap();
+    poh.resume(2).unwrap();
+    assert!(poh.state.iter().all(|entry| entry.appended_data.is_none()));
+    assert_eq!(poh.append_data_entry(b"second".to_vec()).unwrap().interval, 980);
+    assert_eq!(poh.verify(), Ok(()));
+
+    let restored = PoHUsage::from_bytes(&poh.to_bytes().unwrap(), windowed.clone()).unwrap();
+    assert_eq!(restored.data_rounds, poh.data_rounds);
+    let mut forged = restored;
+    forged.state[1].interval = 10;
+    forged.state[1].hash = forged.config.link_hash(&forged.state[0].hash, &forged.state[1]).into();
+    assert_eq!(forged.verify(), Err(VerifyError::DataTooClose { index: 1, rounds: 30, min: 1000 }));
+
+    poh.resume(1).unwrap();
+    let mut resumed = PoHUsage::from_snapshot(poh.snapshot(), windowed, vec![]).unwrap();
+    for poh in [&mut poh, &mut resumed] {
+        poh.append_data_entry(b"third".to_vec()).unwrap();
+    }
+    assert_eq!(resumed.last(), poh.last());
+    assert_eq!(resumed.last().unwrap().interval, 990);
+}
+
+#[test]
+fn entries_expose_their_data() {
+    let text = PoHEntry { appended_data: Some(AppendedData::new(b"hi".to_vec())), ..PoHEntry::default() };
+    assert_eq!(text.data_bytes().as_deref(), Some(&b"hi"[..]));
+    assert_eq!(text.data_hex().as_deref(), Some("6869"));
+    assert_eq!(text.data_as_str().unwrap().as_deref(), Ok("hi"));
+
+    let binary = PoHEntry { appended_data: Some(AppendedData::new(vec![0xff])), ..PoHEntry::default() };
+    assert!(binary.data_as_str().unwrap().is_err());
+
+    let empty = PoHEntry::default();
+    assert_eq!(empty.data_bytes(), None);
+    assert_eq!(empty.data_hex(), None);
+    assert!(empty.data_as_str().is_none());
+}
+
+#[test]
+fn truncated_views_keep_the_hash_prefixes() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(3), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed([76u8; 64]), None, vec![]).unwrap();
+    poh.init().unwrap();
+
+    let view = poh.view_truncated(16);
+    assert_eq!(view.len(), poh.state.len());
+    assert!(view.iter().zip(&poh.state).all(|(short, entry)| short[..] == entry.hash[..16]));
+    assert_eq!(poh.view_truncated(32)[3], poh.state[3].hash);
+    assert!(std::panic::catch_unwind(|| poh.view_truncated(33)).is_err());
+
+    let short = PoHConfig::new(Sha256::new(), 32, 10, Some(2), true, true, TickEntryType::Hash(20));
+    let short = PoHUsage::new(short, InitialSeed([76u8; 64]), None, vec![]).unwrap();
+    assert_eq!(short.view_truncated(20)[0], short.state[0].hash);
+    assert!(std::panic::catch_unwind(|| short.view_truncated(25)).is_err());
+}
+
+#[test]
+fn config_only_chains_start_from_a_zero_seed() {
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(3), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::from_config_only(config.clone());
+    let seeded = PoHUsage::new(config, InitialSeed([0u8; 64]), None, vec![]).unwrap();
+    assert_eq!(poh.genesis(), seeded.genesis());
+    assert!(poh.extensions().is_empty());
+    poh.init().unwrap();
+    assert!(poh.verify().is_ok());
+}
+
+#[test]
+fn stretched_seeds_change_the_genesis() {
+    let seed = InitialSeed([0u8; 64]);
+    assert_eq!(seed.stretched(0), seed);
+    assert_eq!(seed.stretched(3), seed.stretched(2).stretched(1));
+    assert_ne!(seed.stretched(1), seed);
+
+    let plain = PoHConfig::new(Sha256::new(), 32, 10, Some(2), true, true, TickEntryType::Data);
+    let stretched = PoHConfig { stretch_seed: true, ..plain.clone() };
+    let mut poh = PoHUsage::new(stretched.clone(), seed.clone(), Some(b"init".to_vec()), vec![]).unwrap();
+    let expected = PoHUsage::new(plain.clone(), seed.stretched(SEED_STRETCH_ROUNDS), Some(b"init".to_vec()), vec![]).unwrap();
+    assert_eq!(poh.genesis(), expected.genesis());
+    assert_ne!(poh.genesis_hash(), PoHUsage::new(plain.clone(), seed, Some(b"init".to_vec()), vec![]).unwrap().genesis_hash());
+    assert_ne!(stretched.config_hash(), plain.config_hash());
+    poh.init().unwrap();
+    assert!(poh.verify().is_ok());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn run_timings_do_not_affect_equality() {
+    use core::hash::{BuildHasher, Hash, Hasher};
+    use digest::{FixedOutput, HashMarker, Output, OutputSizeUser, Update};
+
+    /// SHA-256 that compares equal to every other instance, as stateless hashers do.
+    #[derive(Clone, Default)]
+    struct Comparable(Sha256);
+    impl PartialEq for Comparable {
+        fn eq(&self, _: &Self) -> bool {
+            true
+        }
+    }
+    impl Eq for Comparable {}
+    impl Hash for Comparable {
+        fn hash<H: Hasher>(&self, _: &mut H) {}
+    }
+    impl OutputSizeUser for Comparable {
+        type OutputSize = <Sha256 as OutputSizeUser>::OutputSize;
+    }
+    impl Update for Comparable {
+        fn update(&mut self, data: &[u8]) {
+            Update::update(&mut self.0, data);
+        }
+    }
+    impl FixedOutput for Comparable {
+        fn finalize_into(self, out: &mut Output<Self>) {
+            FixedOutput::finalize_into(self.0, out);
+        }
+    }
+    impl HashMarker for Comparable {}
+
+    let config = PoHConfig::new(Comparable::default(), 32, 10, Some(50), true, true, TickEntryType::Data);
+    let mut a = PoHUsage::new(config.clone(), InitialSeed([78u8; 64]), None, vec![]).unwrap().with_id(1);
+    let mut b = PoHUsage::new(config, InitialSeed([78u8; 64]), None, vec![]).unwrap().with_id(1);
+    a.init().unwrap();
+    b.init().unwrap();
+    b.last_run = Some(RunStats { elapsed: std::time::Duration::from_secs(1), entries: 50 });
+    assert_ne!(a.last_run, b.last_run);
+    assert!(a == b);
+    let hasher = std::collections::hash_map::RandomState::new();
+    assert_eq!(hasher.hash_one(&a), hasher.hash_one(&b));
+
+    b.state.pop();
+    assert!(a != b);
+}
@@ -0,0 +1,404 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Binary Merkle tree over entry hashes, used by the `MerkleTree` extension.
+//!
+//! Leaves are `H(0x00 || entry hash)` and inner nodes are `H(0x01 || left || right)`,
+//! computed with the configured hasher. When a level has an odd number of nodes
+//! the last node is promoted to the next level unchanged. Digests shorter than
+//! 32 bytes are zero-padded and longer digests are truncated to 32 bytes.
+//!
+//! While the extension is active the chain also keeps a Merkle mountain range,
+//! the roots ("peaks") of the perfect subtrees over the entries seen so far, one
+//! for every set bit of the entry count. Appending an entry merges peaks of equal
+//! height, so the root is kept up to date in O(log n) hashes. Bagging the peaks
+//! from the right, `H(0x01 || peak || bagged rest)`, yields exactly the root of
+//! the tree described above.
+
+use alloc::vec::Vec;
+
+use digest::Digest;
+
+use crate::extensions::Extensions;
+use crate::{PoHEntry, PoHUsage};
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+impl<D: Digest + Clone> PoHUsage<D> {
+    /// Root of the Merkle tree over every entry hash.
+    /// Returns `None` unless the `MerkleTree` extension is active.
+    #[must_use]
+    pub fn merkle_root(&self) -> Option<[u8; 32]> {
+        if !self.has_extension(Extensions::MerkleTree) || self.state.is_empty() {
+            return None;
+        }
+        if self.mmr.holds(&self.config.hasher, &self.state) {
+            return self.mmr.root(&self.config.hasher);
+        }
+        root_of(&self.config.hasher, self.state.iter().map(|entry| entry.hash.as_slice()))
+    }
+
+    /// Root of the Merkle mountain range over every entry hash, equal to
+    /// `merkle_root`, bagged from the peaks kept up to date as entries are appended.
+    /// Returns `None` unless the `MerkleTree` extension is active.
+    #[must_use]
+    pub fn mmr_root(&self) -> Option<[u8; 32]> {
+        if !self.has_extension(Extensions::MerkleTree) {
+            return None;
+        }
+        self.current_mmr().root(&self.config.hasher)
+    }
+
+    /// Proof that the entry at `index` is part of `mmr_root`: the sibling hashes up
+    /// to the peak covering the entry, and every peak of the range.
+    /// Returns `None` unless the `MerkleTree` extension is active and `index` is in range.
+    #[must_use]
+    pub fn mmr_proof(&self, index: usize) -> Option<MmrProof> {
+        if !self.has_extension(Extensions::MerkleTree) || index >= self.state.len() {
+            return None;
+        }
+        let (start, height) = peak_covering(self.state.len(), index)?;
+        let mut proof = Vec::new();
+        let mut level: Vec<[u8; 32]> = self.state[start..start + (1 << height)]
+            .iter()
+            .map(|entry| leaf_hash(&self.config.hasher, &entry.hash))
+            .collect();
+        let mut position = index - start;
+        while level.len() > 1 {
+            proof.push(level[position ^ 1]);
+            level = next_level(&self.config.hasher, &level);
+            position /= 2;
+        }
+        let peaks = self.current_mmr().peaks.into_iter().map(|(peak, _)| peak).collect();
+        Some(MmrProof { leaf_count: self.state.len(), path: proof, peaks })
+    }
+
+    /// Sibling hashes proving that the entry at `index` is part of `merkle_root`,
+    /// ordered from the leaf level upwards.
+    /// Returns `None` unless the `MerkleTree` extension is active and `index` is in range.
+    #[must_use]
+    pub fn merkle_proof(&self, index: usize) -> Option<Vec<[u8; 32]>> {
+        if !self.has_extension(Extensions::MerkleTree) || index >= self.state.len() {
+            return None;
+        }
+        let mut proof = Vec::new();
+        let mut level = self.merkle_leaves();
+        let mut position = index;
+        while level.len() > 1 {
+            if let Some(sibling) = level.get(position ^ 1) {
+                proof.push(*sibling);
+            }
+            level = next_level(&self.config.hasher, &level);
+            position /= 2;
+        }
+        Some(proof)
+    }
+
+    /// Same as `merkle_root`, hashing the leaves and every tree level in parallel
+    /// with `rayon`. The result is bit-identical to `merkle_root`.
+    #[cfg(feature = "parallel")]
+    #[must_use]
+    pub fn merkle_root_parallel(&self) -> Option<[u8; 32]>
+    where
+        D: Send + Sync,
+    {
+        use rayon::prelude::*;
+
+        if !self.has_extension(Extensions::MerkleTree) || self.state.is_empty() {
+            return None;
+        }
+        let hasher = &self.config.hasher;
+        let mut level: Vec<[u8; 32]> = self.state.par_iter().map(|entry| leaf_hash(hasher, &entry.hash)).collect();
+        while level.len() > 1 {
+            level = level.par_chunks(2).map(|pair| pair_hash(hasher, pair)).collect();
+        }
+        Some(level[0])
+    }
+
+    fn merkle_leaves(&self) -> Vec<[u8; 32]> {
+        self.state.iter().map(|entry| leaf_hash(&self.config.hasher, &entry.hash)).collect()
+    }
+
+    /// Brings the mountain range up to date after an entry was pushed, adding the
+    /// newest entry when only it is missing and rebuilding it otherwise, e.g. after
+    /// `truncate` or pruning.
+    pub(crate) fn sync_mmr(&mut self) {
+        let Some((newest, held)) = self.state.split_last() else {
+            return;
+        };
+        if !self.has_extension(Extensions::MerkleTree) {
+            return;
+        }
+        if self.mmr.holds(&self.config.hasher, held) {
+            self.mmr.push(&self.config.hasher, &newest.hash);
+        } else {
+            self.mmr = Mmr::build(&self.config.hasher, &self.state);
+        }
+    }
+
+    fn current_mmr(&self) -> Mmr {
+        if self.mmr.holds(&self.config.hasher, &self.state) {
+            self.mmr.clone()
+        } else {
+            Mmr::build(&self.config.hasher, &self.state)
+        }
+    }
+}
+
+/// Proof produced by `PoHUsage::mmr_proof`, checked by `verify_mmr_proof`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MmrProof {
+    /// Number of entries the mountain range covered when the proof was made.
+    pub leaf_count: usize,
+    /// Sibling hashes from the leaf up to its peak.
+    pub path: Vec<[u8; 32]>,
+    /// Every peak of the range, from the highest (oldest entries) to the lowest.
+    pub peaks: Vec<[u8; 32]>,
+}
+
+/// Peaks of the Merkle mountain range over the entry hashes, with their heights.
+///
+/// The range is a cache of the held entries: it takes no part in comparing or
+/// hashing chains, and it is only trusted while `holds` confirms that it still
+/// matches them.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Mmr {
+    leaves: usize,
+    newest: [u8; 32],
+    peaks: Vec<([u8; 32], u32)>,
+}
+
+impl Mmr {
+    fn build<D: Digest + Clone>(hasher: &D, entries: &[PoHEntry]) -> Self {
+        let mut mmr = Mmr::default();
+        for entry in entries {
+            mmr.push(hasher, &entry.hash);
+        }
+        mmr
+    }
+
+    fn push<D: Digest + Clone>(&mut self, hasher: &D, entry_hash: &[u8]) {
+        let leaf = leaf_hash(hasher, entry_hash);
+        let (mut node, mut height) = (leaf, 0);
+        while let Some(&(left, _)) = self.peaks.last().filter(|(_, peak_height)| *peak_height == height) {
+            self.peaks.pop();
+            node = node_hash(hasher, &left, &node);
+            height += 1;
+        }
+        self.peaks.push((node, height));
+        self.leaves += 1;
+        self.newest = leaf;
+    }
+
+    /// Whether the range covers exactly `entries`, judged by their count and the
+    /// newest entry.
+    fn holds<D: Digest + Clone>(&self, hasher: &D, entries: &[PoHEntry]) -> bool {
+        match entries.last() {
+            Some(newest) => self.leaves == entries.len() && self.newest == leaf_hash(hasher, &newest.hash),
+            None => self.leaves == 0,
+        }
+    }
+
+    fn root<D: Digest + Clone>(&self, hasher: &D) -> Option<[u8; 32]> {
+        bag(hasher, self.peaks.iter().map(|(peak, _)| peak))
+    }
+}
+
+/// Start and height of the peak covering `index` in a range of `leaf_count` leaves.
+fn peak_covering(leaf_count: usize, index: usize) -> Option<(usize, u32)> {
+    let mut start = 0;
+    for height in (0..usize::BITS).rev().filter(|height| leaf_count >> height & 1 == 1) {
+        if index < start + (1 << height) {
+            return Some((start, height));
+        }
+        start += 1 << height;
+    }
+    None
+}
+
+/// Combines peaks from the right, as in `H(0x01 || peak || bagged rest)`.
+fn bag<'a, D: Digest + Clone>(hasher: &D, peaks: impl DoubleEndedIterator<Item = &'a [u8; 32]>) -> Option<[u8; 32]> {
+    peaks.rev().fold(None, |bagged, peak| match bagged {
+        Some(right) => Some(node_hash(hasher, peak, &right)),
+        None => Some(*peak),
+    })
+}
+
+/// Root of the Merkle tree over `hashes`, built like `PoHUsage::merkle_root`.
+/// Returns `None` when there are no hashes.
+pub(crate) fn root_of<'a, D: Digest + Clone>(hasher: &D, hashes: impl IntoIterator<Item = &'a [u8]>) -> Option<[u8; 32]> {
+    let mut level: Vec<[u8; 32]> = hashes.into_iter().map(|hash| leaf_hash(hasher, hash)).collect();
+    if level.is_empty() {
+        return None;
+    }
+    while level.len() > 1 {
+        level = next_level(hasher, &level);
+    }
+    Some(level[0])
+}
+
+/// Checks a proof produced by `PoHUsage::merkle_proof`.
+///
+/// `entry_hash` is the hash of the entry at `index` in a chain of `leaf_count`
+/// entries, and `hasher` must be the hasher the chain was configured with.
+#[must_use]
+pub fn verify_merkle_proof<D: Digest + Clone>(
+    hasher: D,
+    root: &[u8; 32],
+    entry_hash: &[u8],
+    index: usize,
+    leaf_count: usize,
+    proof: &[[u8; 32]],
+) -> bool {
+    if index >= leaf_count {
+        return false;
+    }
+    let mut node = leaf_hash(&hasher, entry_hash);
+    let mut siblings = proof.iter();
+    let (mut position, mut len) = (index, leaf_count);
+    while len > 1 {
+        if position % 2 == 1 {
+            match siblings.next() {
+                Some(sibling) => node = node_hash(&hasher, sibling, &node),
+                None => return false,
+            }
+        } else if position + 1 < len {
+            match siblings.next() {
+                Some(sibling) => node = node_hash(&hasher, &node, sibling),
+                None => return false,
+            }
+        }
+        position /= 2;
+        len = len.div_ceil(2);
+    }
+    siblings.next().is_none() && crate::verify::hashes_equal(&node, root)
+}
+
+/// Checks a proof produced by `PoHUsage::mmr_proof`.
+///
+/// `entry_hash` is the hash of the entry at `index`, and `hasher` must be the
+/// hasher the chain was configured with.
+#[must_use]
+pub fn verify_mmr_proof<D: Digest + Clone>(hasher: D, root: &[u8; 32], entry_hash: &[u8], index: usize, proof: &MmrProof) -> bool {
+    let Some((start, height)) = peak_covering(proof.leaf_count, index) else {
+        return false;
+    };
+    if proof.path.len() != height as usize || proof.peaks.len() != proof.leaf_count.count_ones() as usize {
+        return false;
+    }
+    let mut node = leaf_hash(&hasher, entry_hash);
+    let mut position = index - start;
+    for sibling in &proof.path {
+        node = match position % 2 {
+            0 => node_hash(&hasher, &node, sibling),
+            _ => node_hash(&hasher, sibling, &node),
+        };
+        position /= 2;
+    }
+    let peak = (proof.leaf_count >> (height + 1)).count_ones() as usize;
+    crate::verify::hashes_equal(&node, &proof.peaks[peak])
+        && bag(&hasher, proof.peaks.iter()).is_some_and(|bagged| crate::verify::hashes_equal(&bagged, root))
+}
+
+/// Hashes `parts` with a fresh clone of `hasher` into a 32-byte value.
+pub(crate) fn hash_32<D: Digest + Clone>(hasher: &D, parts: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = hasher.clone();
+    for part in parts {
+        hasher.update(part);
+    }
+    let output = hasher.finalize();
+    let mut result = [0u8; 32];
+    let len = output.len().min(32);
+    result[..len].copy_from_slice(&output[..len]);
+    result
+}
+
+fn leaf_hash<D: Digest + Clone>(hasher: &D, entry_hash: &[u8]) -> [u8; 32] {
+    hash_32(hasher, &[&[LEAF_PREFIX], entry_hash])
+}
+
+fn node_hash<D: Digest + Clone>(hasher: &D, left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    hash_32(hasher, &[&[NODE_PREFIX], left, right])
+}
+
+fn next_level<D: Digest + Clone>(hasher: &D, level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    level.chunks(2).map(|pair| pair_hash(hasher, pair)).collect()
+}
+
+/// Parent of a chunk of one or two nodes; a lone node is promoted unchanged.
+fn pair_hash<D: Digest + Clone>(hasher: &D, pair: &[[u8; 32]]) -> [u8; 32] {
+    match pair {
+        [left, right] => node_hash(hasher, left, right),
+        [single] => *single,
+        _ => unreachable!("chunks of two"),
+    }
+}
+
+#[test]
+fn merkle_proofs_verify_every_entry() {
+    use crate::{InitialSeed, PoHConfig, TickEntryType};
+
+    let config = PoHConfig::new(sha2::Sha256::new(), 32, 5, Some(6), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config.clone(), InitialSeed([11; 64]), None, vec![Extensions::MerkleTree]).unwrap();
+    poh.init().unwrap();
+
+    let root = poh.merkle_root().unwrap();
+    let len = poh.state.len();
+    for index in 0..len {
+        let proof = poh.merkle_proof(index).unwrap();
+        let hash = &poh.state[index].hash;
+        assert!(verify_merkle_proof(sha2::Sha256::new(), &root, hash, index, len, &proof));
+        assert!(!verify_merkle_proof(sha2::Sha256::new(), &root, hash, (index + 1) % len, len, &proof));
+    }
+
+    let plain = PoHUsage::new(config, InitialSeed([11; 64]), None, vec![]).unwrap();
+    assert_eq!(plain.merkle_root(), None);
+    assert_eq!(plain.merkle_proof(0), None);
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn parallel_merkle_root_matches_sequential() {
+    use crate::{InitialSeed, PoHConfig, TickEntryType};
+
+    for len in [1, 2, 7, 64] {
+        let config = PoHConfig::new(sha2::Sha256::new(), 32, 3, Some(len), true, true, TickEntryType::Data);
+        let mut poh = PoHUsage::new(config, InitialSeed([12; 64]), None, vec![Extensions::MerkleTree]).unwrap();
+        poh.init().unwrap();
+        assert_eq!(poh.merkle_root_parallel(), poh.merkle_root());
+    }
+}
+
+#[test]
+fn mountain_ranges_track_the_merkle_root() {
+    use crate::{InitialSeed, PoHConfig, TickEntryType};
+
+    let config = PoHConfig::new(sha2::Sha256::new(), 32, 5, Some(20), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config.clone(), InitialSeed([77; 64]), None, vec![Extensions::MerkleTree]).unwrap();
+    for len in 1..=21 {
+        let root = root_of(&poh.config.hasher, poh.state.iter().map(|entry| entry.hash.as_slice()));
+        assert_eq!(poh.mmr.leaves, len);
+        assert_eq!(poh.mmr_root(), root);
+        assert_eq!(poh.merkle_root(), root);
+        for index in 0..len {
+            let proof = poh.mmr_proof(index).unwrap();
+            let hash = &poh.state[index].hash;
+            assert!(verify_mmr_proof(sha2::Sha256::new(), &root.unwrap(), hash, index, &proof));
+            assert!(!verify_mmr_proof(sha2::Sha256::new(), &root.unwrap(), hash, (index + 1) % len, &proof) || len == 1);
+        }
+        if len <= 20 {
+            poh.append_data_entry(vec![len as u8]).unwrap();
+        }
+    }
+
+    let copy = PoHUsage::from_state(config.clone(), poh.state.clone(), vec![Extensions::MerkleTree]).unwrap();
+    assert_eq!(copy.merkle_root(), poh.merkle_root());
+    poh.truncate(6);
+    assert_eq!(poh.mmr_root(), copy.fork(5).unwrap().merkle_root());
+    poh.resume(1).unwrap();
+    assert_eq!(poh.mmr.leaves, 7);
+
+    let plain = PoHUsage::new(config, InitialSeed([77; 64]), None, vec![]).unwrap();
+    assert_eq!(plain.mmr_root(), None);
+    assert_eq!(plain.mmr_proof(0), None);
+}
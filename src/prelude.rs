@@ -2,7 +2,17 @@
 pub use crate::PoHConfig;
 pub use crate::PoHUsage;
 pub use crate::PoHEntry;
+pub use crate::EntryHash;
 
 /// The initial seed for the PoH process.
 pub use crate::InitialSeed;
-pub use crate::AppendedData;
\ No newline at end of file
+pub use crate::AppendedData;
+pub use crate::EventHash;
+pub use crate::TickEntryType;
+pub use crate::extensions::Extensions;
+pub use crate::{EventData, Seedable};
+pub use crate::{PoHError, VerifyError};
+
+/// Wall-clock helpers behind the `Timestamp` and `TimestampRFC3339` extensions.
+#[cfg(feature = "std")]
+pub use crate::timestamps::{get_current_timestamp, get_current_timestamp_rfc3339, parse_rfc3339, rfc3339_to_unix};
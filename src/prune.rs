@@ -0,0 +1,176 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Pruning old entries while keeping a commitment to them.
+//!
+//! `prune_before` removes a prefix of the chain and replaces it with a 32-byte
+//! commitment: the root of a Merkle tree (as built by the `merkle` module) whose
+//! leaves are the previous commitment, if the chain was pruned before, followed
+//! by the hashes of the removed entries. The remaining chain still verifies from
+//! its new first entry, and anyone holding the removed entries can check them
+//! against the commitment with `verify_pruned`. The commitment is part of the
+//! binary encoding, so a pruned chain that is saved and reloaded can still check
+//! them.
+//!
+//! With `PoHConfig::window` set, the chain prunes itself one entry at a time as
+//! it grows, holding a constant number of entries for unbounded streams.
+
+use alloc::vec::Vec;
+
+use digest::Digest;
+
+use crate::errors::{PoHError, VerifyError};
+use crate::merkle::root_of;
+use crate::verify::hashes_equal;
+use crate::{PoHEntry, PoHUsage};
+
+impl<D: Digest + Clone> PoHUsage<D> {
+    /// Removes entries `0..index` and returns the commitment that replaces them.
+    ///
+    /// At least one entry is always kept, so `index` must be between 1 and the
+    /// index of the last entry. Milestones marking removed entries are dropped and
+    /// the others keep marking the same entries. Ids are unchanged, so entries
+    /// keep their position in the full chain and `max_entries` still counts the
+    /// pruned entries. The genesis hash is kept, so `commitment` and
+    /// `fingerprint` do not change.
+    pub fn prune_before(&mut self, index: usize) -> Result<[u8; 32], PoHError> {
+        if index == 0 || index >= self.state.len() {
+            return Err(PoHError::IndexOutOfRange { index, len: self.state.len() });
+        }
+        self.data_rounds = self.data_rounds_at(index);
+        self.last_nonce = self.state[..=index].iter().rev().find_map(|entry| entry.nonce).or(self.last_nonce);
+        let removed: Vec<PoHEntry> = self.state.drain(..index).collect();
+        if self.genesis_hash.is_none() {
+            self.genesis_hash = Some(removed[0].hash.clone());
+        }
+        let commitment = commit(&self.config.hasher, self.pruned_commitment.as_ref(), &removed);
+        self.pruned_commitment = Some(commitment);
+        self.milestones.retain(|(_, marked)| *marked >= index);
+        for (_, marked) in &mut self.milestones {
+            *marked -= index;
+        }
+        #[cfg(feature = "ed25519")]
+        self.retain_signatures();
+        Ok(commitment)
+    }
+
+    /// Commitment to every entry removed by `prune_before`, if any were removed.
+    #[must_use]
+    pub fn pruned_commitment(&self) -> Option<&[u8; 32]> {
+        self.pruned_commitment.as_ref()
+    }
+
+    /// Checks that `pruned` are the entries removed by the most recent
+    /// `prune_before`, where `prior` is the commitment the chain held before it.
+    ///
+    /// The pruned entries must verify among themselves, the last one must link to
+    /// the first remaining entry, and together with `prior` they must reproduce
+    /// `pruned_commitment`. Fails with `NothingPruned` when `pruned` is empty or
+    /// the chain holds no commitment.
+    #[must_use = "the pruned entries are only verified if the result is checked"]
+    pub fn verify_pruned(&self, prior: Option<&[u8; 32]>, pruned: &[PoHEntry]) -> Result<(), VerifyError> {
+        let (Some(commitment), Some(last), Some(first)) = (self.pruned_commitment.as_ref(), pruned.last(), self.state.first()) else {
+            return Err(VerifyError::NothingPruned);
+        };
+        for pair in pruned.windows(2) {
+            Self::verify_link(&pair[0], &pair[1], &self.config)?;
+        }
+        Self::verify_link(last, first, &self.config)?;
+        let expected = commit(&self.config.hasher, prior, pruned);
+        if !hashes_equal(&expected, commitment) {
+            return Err(VerifyError::CommitmentMismatch);
+        }
+        Ok(())
+    }
+}
+
+fn commit<D: Digest + Clone>(hasher: &D, prior: Option<&[u8; 32]>, removed: &[PoHEntry]) -> [u8; 32] {
+    let prior = prior.map(|commitment| commitment.as_slice());
+    let hashes = prior.into_iter().chain(removed.iter().map(|entry| entry.hash.as_slice()));
+    root_of(hasher, hashes).expect("at least one entry is pruned")
+}
+
+#[test]
+fn pruned_chains_stay_verifiable() {
+    use crate::extensions::Extensions;
+    use crate::{InitialSeed, PoHConfig, TickEntryType};
+
+    let config = PoHConfig::new(sha2::Sha256::new(), 32, 10, Some(8), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed([27; 64]), None, vec![Extensions::Milestone]).unwrap();
+    poh.resume(2).unwrap();
+    poh.add_milestone("early".into()).unwrap();
+    poh.resume(3).unwrap();
+    poh.add_milestone("late".into()).unwrap();
+    let full = poh.state.clone();
+
+    assert_eq!(poh.verify_pruned(None, &full[..3]), Err(VerifyError::NothingPruned));
+    let first = poh.prune_before(3).unwrap();
+    assert_eq!(poh.state, full[3..]);
+    assert_eq!(poh.pruned_commitment(), Some(&first));
+    assert_eq!(poh.milestones(), &[("late".to_string(), 2)]);
+    assert!(poh.verify().is_ok());
+    assert_eq!(poh.verify_pruned(None, &full[..3]), Ok(()));
+    assert_eq!(poh.verify_pruned(None, &full[1..3]), Err(VerifyError::CommitmentMismatch));
+    assert_eq!(poh.verify_pruned(None, &[]), Err(VerifyError::NothingPruned));
+
+    let restored = PoHUsage::from_bytes(&poh.to_bytes().unwrap(), poh.config.clone()).unwrap();
+    assert_eq!(restored.pruned_commitment(), Some(&first));
+    assert_eq!(restored.verify(), Ok(()));
+    assert_eq!(restored.verify_pruned(None, &full[..3]), Ok(()));
+    assert_eq!(restored.genesis_hash(), full[0].hash.as_slice());
+    let mut forgotten = restored.clone();
+    forgotten.genesis_hash = None;
+    assert_eq!(forgotten.verify(), Err(VerifyError::Malformed { index: 0, reason: "a pruned chain does not hold the genesis hash" }));
+    let mut unpruned = PoHUsage::from_state(poh.config.clone(), full.clone(), vec![Extensions::Milestone]).unwrap();
+    unpruned.pruned_commitment = Some(first);
+    let malformed = VerifyError::Malformed { index: 0, reason: "the genesis entry is held along with a pruning commitment" };
+    assert_eq!(unpruned.verify(), Err(malformed));
+
+    let second = poh.prune_before(2).unwrap();
+    assert_ne!(second, first);
+    assert_eq!(poh.verify_pruned(Some(&first), &full[3..5]), Ok(()));
+    assert!(poh.verify_pruned(Some(&first), &full[2..4]).is_err());
+
+    // Pruned entries still count towards max_entries.
+    assert_eq!(poh.len(), 1);
+    assert_eq!(poh.resume(4), Err(PoHError::MaxEntriesReached { max: 8 }));
+    poh.resume(3).unwrap();
+    assert!(poh.verify().is_ok());
+
+    assert_eq!(poh.prune_before(0), Err(PoHError::IndexOutOfRange { index: 0, len: 4 }));
+    assert_eq!(poh.prune_before(4), Err(PoHError::IndexOutOfRange { index: 4, len: 4 }));
+}
+
+#[test]
+fn windows_bound_the_held_entries() {
+    use crate::{InitialSeed, PoHConfig, TickEntryType};
+
+    let config = PoHConfig::new(sha2::Sha256::new(), 32, 5, Some(20), true, true, TickEntryType::Data);
+    let mut full = PoHUsage::new(config.clone(), InitialSeed([66; 64]), None, vec![]).unwrap();
+    full.resume(10).unwrap();
+
+    let windowed_config = PoHConfig::builder(sha2::Sha256::new()).tick_interval(5).max_entries(Some(20)).window(Some(3)).build().unwrap();
+    let mut windowed = PoHUsage::new(windowed_config, InitialSeed([66; 64]), None, vec![]).unwrap();
+    assert_eq!(windowed.tick_n(5).unwrap().len(), 3);
+    windowed.resume(5).unwrap();
+
+    assert_eq!(windowed.len(), 3);
+    assert_eq!(windowed.get_state(), &full.get_state()[8..]);
+    assert_eq!(windowed.verify(), Ok(()));
+    let added = windowed.append_data_batch(vec![b"a".to_vec(), b"b".to_vec()]).unwrap();
+    assert_eq!(added.len(), 2);
+    assert_eq!(windowed.len(), 3);
+
+    let commitment = *windowed.pruned_commitment().unwrap();
+    let mut manual = full.clone();
+    for index in 1..=8 {
+        manual.prune_before(1).unwrap();
+        assert_eq!(manual.state[0].id, index);
+    }
+    manual.append_data_batch(vec![b"a".to_vec(), b"b".to_vec()]).unwrap();
+    manual.prune_before(1).unwrap();
+    manual.prune_before(1).unwrap();
+    assert_eq!(manual.get_state(), windowed.get_state());
+    assert_eq!(manual.pruned_commitment(), Some(&commitment));
+
+    assert_eq!(PoHConfig { window: Some(0), ..config }.validate(), Err(PoHError::InvalidConfig("window must be greater than zero")));
+}
@@ -0,0 +1,46 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Hex encoding of byte fields for the `serde` feature.
+//! Used through `#[serde(with = "crate::serde_hex")]` so that hashes, seeds and
+//! appended data round-trip as readable strings.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serializer};
+
+pub(crate) fn serialize<S: Serializer, T: AsRef<[u8]>>(bytes: T, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&hex::encode(bytes))
+}
+
+pub(crate) fn deserialize<'de, De: Deserializer<'de>, T: TryFrom<Vec<u8>>>(deserializer: De) -> Result<T, De::Error> {
+    let encoded = String::deserialize(deserializer)?;
+    let bytes = hex::decode(&encoded).map_err(De::Error::custom)?;
+    let len = bytes.len();
+    T::try_from(bytes).map_err(|_| De::Error::custom(format!("unexpected byte length {}", len)))
+}
+
+/// Hex encoding of optional byte fields, used through
+/// `#[serde(default, with = "crate::serde_hex::option")]`.
+pub(crate) mod option {
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(bytes: &Option<Vec<u8>>, serializer: S) -> Result<S::Ok, S::Error> {
+        match bytes {
+            Some(bytes) => serializer.serialize_some(&hex::encode(bytes)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub(crate) fn deserialize<'de, De: Deserializer<'de>>(deserializer: De) -> Result<Option<Vec<u8>>, De::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|encoded| hex::decode(encoded).map_err(De::Error::custom))
+            .transpose()
+    }
+}
@@ -0,0 +1,84 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Ed25519 signatures on milestones.
+//!
+//! A signed milestone carries a signature over the hash of the entry it marks and
+//! its name, so downstream parties can check who published it. Signatures are
+//! kept alongside the milestones and are not part of the binary encoding.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use digest::Digest;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+
+use crate::errors::PoHError;
+use crate::PoHUsage;
+
+/// Tag prefixed to every signed milestone message.
+const SIGNATURE_TAG: &[u8] = b"poh-yugen/milestone";
+
+impl<D: Digest + Clone> PoHUsage<D> {
+    /// Same as `add_milestone`, additionally signing the hash of the current tip
+    /// and `name` with `signing_key`.
+    pub fn add_signed_milestone(&mut self, name: String, signing_key: &SigningKey) -> Result<(), PoHError> {
+        let tip = self.state.len() - 1;
+        let signature = signing_key.sign(&milestone_message(&self.state[tip].hash, &name));
+        self.add_milestone(name.clone())?;
+        self.signatures.push((name, signature.to_bytes()));
+        Ok(())
+    }
+
+    /// Whether the milestone called `name` was signed with the key matching
+    /// `public_key` over the entry it marks.
+    #[must_use]
+    pub fn verify_milestone_signature(&self, name: &str, public_key: &VerifyingKey) -> bool {
+        let (Some(index), Some(signature)) = (self.milestone(name), self.milestone_signature(name)) else {
+            return false;
+        };
+        public_key.verify(&milestone_message(&self.state[index].hash, name), &signature).is_ok()
+    }
+
+    /// Signature of the milestone called `name`, if it was added with
+    /// `add_signed_milestone`.
+    #[must_use]
+    pub fn milestone_signature(&self, name: &str) -> Option<Signature> {
+        self.signatures.iter().find(|(signed, _)| signed == name).map(|(_, bytes)| Signature::from_bytes(bytes))
+    }
+
+    /// Drops the signatures of milestones that no longer exist.
+    pub(crate) fn retain_signatures(&mut self) {
+        let milestones = &self.milestones;
+        self.signatures.retain(|(name, _)| milestones.iter().any(|(milestone, _)| milestone == name));
+    }
+}
+
+fn milestone_message(hash: &[u8], name: &str) -> Vec<u8> {
+    [SIGNATURE_TAG, hash, name.as_bytes()].concat()
+}
+
+#[test]
+fn signed_milestones_verify_with_the_signing_key() {
+    use crate::extensions::Extensions;
+    use crate::{InitialSeed, PoHConfig, TickEntryType};
+
+    let key = SigningKey::from_bytes(&[46; 32]);
+    let other = SigningKey::from_bytes(&[47; 32]);
+    let config = PoHConfig::new(sha2::Sha256::new(), 32, 10, Some(4), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed([48; 64]), None, vec![Extensions::Milestone]).unwrap();
+    poh.resume(1).unwrap();
+    poh.add_signed_milestone("release".into(), &key).unwrap();
+    poh.add_milestone("unsigned".into()).unwrap();
+    poh.resume(1).unwrap();
+
+    assert!(poh.verify_milestone_signature("release", &key.verifying_key()));
+    assert!(!poh.verify_milestone_signature("release", &other.verifying_key()));
+    assert!(!poh.verify_milestone_signature("unsigned", &key.verifying_key()));
+    assert!(!poh.verify_milestone_signature("missing", &key.verifying_key()));
+    assert_eq!(poh.add_signed_milestone("release".into(), &key), Err(PoHError::DuplicateMilestone("release".into())));
+
+    let fork = poh.fork(2).unwrap();
+    assert!(fork.verify_milestone_signature("release", &key.verifying_key()));
+    poh.truncate(1);
+    assert_eq!(poh.milestone_signature("release"), None);
+}
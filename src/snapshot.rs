@@ -0,0 +1,182 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Snapshots of the generator state, for producers that stream entries out
+//! instead of keeping them.
+//!
+//! A snapshot holds everything needed to keep extending a chain: the hash and id
+//! of the tip, the genesis hash, the last nonce, the rounds since the last data entry, the
+//! milestones waiting to be bound and the extensions, plus a digest of the configuration it was taken with.
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use digest::Digest;
+
+use crate::errors::PoHError;
+use crate::extensions::{self, Extensions};
+use crate::merkle::hash_32;
+use crate::{DataMixMode, Endianness, EntryHash, PoHConfig, PoHEntry, PoHUsage, TickEntryType};
+
+/// Tag opening the digest of a configuration.
+const CONFIG_TAG: &[u8] = b"poh-yugen/config";
+
+/// Generator state captured by `PoHUsage::snapshot`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GeneratorSnapshot {
+    /// Hash of the tip.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    pub tip_hash: Vec<u8>,
+    /// Id of the tip, i.e. its position in the full chain.
+    pub tip_id: u64,
+    /// `PoHUsage::genesis_hash` of the chain.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    pub genesis_hash: Vec<u8>,
+    /// Nonce of the most recent data entry, if any.
+    pub last_nonce: Option<u64>,
+    /// Hash rounds generated since the most recent data entry, if any, which the
+    /// next data entry counts towards `min_interval_between_data`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub rounds_since_data: Option<u64>,
+    /// Milestones marking the tip, to be bound by the next entry.
+    pub pending_milestones: Vec<String>,
+    /// `PoHConfig::config_hash` of the configuration.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    pub config_hash: [u8; 32],
+    /// Active extensions.
+    pub extensions: Vec<Extensions>,
+}
+
+impl<D: Digest + Clone> PoHConfig<D> {
+    /// Digest of every parameter of the configuration, computed with its hasher.
+    /// Two configurations with the same hasher produce the same chains exactly
+    /// when their digests match.
+    #[must_use]
+    pub fn config_hash(&self) -> [u8; 32] {
+        let mut params = Vec::new();
+        params.extend_from_slice(&(<D as Digest>::output_size() as u64).to_le_bytes());
+        params.extend_from_slice(&(self.output_size as u64).to_le_bytes());
+        params.extend_from_slice(&self.tick_interval.to_le_bytes());
+        write_option(&mut params, self.max_entries);
+        params.push(u8::from(self.allow_data_entries));
+        params.push(u8::from(self.allow_empty_entries));
+        write_option(&mut params, self.max_data_len);
+        params.push(u8::from(self.store_prev_links));
+        params.push(match self.data_mix {
+            DataMixMode::Prefix => 0,
+            DataMixMode::Suffix => 1,
+            DataMixMode::Interleaved => 2,
+        });
+        params.push(match self.endianness {
+            Endianness::Little => 0,
+            Endianness::Big => 1,
+        });
+        let (tag, len) = match self.tick_entry_type {
+            TickEntryType::Data => (0, 0),
+            TickEntryType::Empty => (1, 0),
+            TickEntryType::ByteString => (2, 0),
+            TickEntryType::UTF8String => (3, 0),
+            TickEntryType::Hash(n) => (4, n),
+            TickEntryType::EventHash28 => (5, 0),
+            TickEntryType::EventHash32 => (6, 0),
+            TickEntryType::EventHash48 => (7, 0),
+            TickEntryType::EventHash64 => (8, 0),
+        };
+        params.push(tag);
+        params.extend_from_slice(&(len as u64).to_le_bytes());
+        // Appended only when set, so digests of earlier configurations are unchanged.
+        if self.min_interval_between_data != 0 {
+            params.extend_from_slice(&self.min_interval_between_data.to_le_bytes());
+        }
+        if self.stretch_seed {
+            params.push(1);
+        }
+        hash_32(&self.hasher, &[CONFIG_TAG, &params])
+    }
+}
+
+fn write_option(out: &mut Vec<u8>, value: Option<usize>) {
+    match value {
+        Some(value) => {
+            out.push(1);
+            out.extend_from_slice(&(value as u64).to_le_bytes());
+        }
+        None => out.push(0),
+    }
+}
+
+impl<D: Digest + Clone> PoHUsage<D> {
+    /// Captures what is needed to continue the chain with `from_snapshot`.
+    #[must_use]
+    pub fn snapshot(&self) -> GeneratorSnapshot {
+        let tip = self.last().expect("PoH state always holds the genesis entry");
+        GeneratorSnapshot {
+            tip_hash: tip.hash.to_vec(),
+            tip_id: tip.id,
+            genesis_hash: self.genesis_hash().to_vec(),
+            last_nonce: self.latest_nonce(),
+            rounds_since_data: self.data_rounds_at(self.state.len() - 1),
+            pending_milestones: self.pending_milestones(),
+            config_hash: self.config.config_hash(),
+            extensions: self.extensions.clone(),
+        }
+    }
+
+    /// Resumes generation from `snapshot`.
+    ///
+    /// The state holds a single stand-in for the tip carrying only its id and
+    /// hash, and the nonces and spacing of data entries carry on from the
+    /// snapshot, so new entries link to the tip, and data entries are spaced,
+    /// exactly as if the full chain were present. Fails when `config` or `extensions` differ from the ones the
+    /// snapshot was taken with, or when the tip hash does not fit `config`.
+    pub fn from_snapshot(snapshot: GeneratorSnapshot, config: PoHConfig<D>, mut extensions: Vec<Extensions>) -> Result<Self, PoHError> {
+        config.validate()?;
+        extensions::normalize(&mut extensions)?;
+        if config.config_hash() != snapshot.config_hash {
+            return Err(PoHError::InvalidConfig("the snapshot was taken with a different configuration"));
+        }
+        if extensions != snapshot.extensions {
+            return Err(PoHError::InvalidConfig("the snapshot was taken with different extensions"));
+        }
+        let (len, expected) = (snapshot.tip_hash.len(), config.hash_len());
+        let tip_hash = EntryHash::new(snapshot.tip_hash, expected).map_err(|_| PoHError::HashLengthMismatch { index: 0, len, expected })?;
+        let genesis_hash = EntryHash::new(snapshot.genesis_hash, expected)?;
+
+        let tip = PoHEntry { id: snapshot.tip_id, hash: tip_hash, ..PoHEntry::default() };
+        let mut poh = Self::from_parts(config, vec![tip], extensions);
+        poh.data_rounds = snapshot.rounds_since_data;
+        poh.last_nonce = snapshot.last_nonce;
+        if snapshot.tip_id != 0 {
+            poh.genesis_hash = Some(genesis_hash);
+        }
+        poh.milestones = snapshot.pending_milestones.into_iter().map(|name| (name, 0)).collect();
+        poh.ensure_capacity(0)?;
+        Ok(poh)
+    }
+}
+
+#[test]
+fn snapshots_resume_generation() {
+    use crate::InitialSeed;
+
+    let config = PoHConfig::builder(sha2::Sha256::new()).tick_interval(10).max_entries(Some(8)).min_interval_between_data(45).build().unwrap();
+    let extensions = vec![Extensions::Breaker(3), Extensions::Milestone];
+    let mut full = PoHUsage::new(config.clone(), InitialSeed([55; 64]), None, extensions.clone()).unwrap();
+    full.append_data_entry(b"a".to_vec()).unwrap();
+    full.resume(2).unwrap();
+    full.add_milestone("mark".into()).unwrap();
+
+    let mut light = PoHUsage::from_snapshot(full.snapshot(), config.clone(), extensions.clone()).unwrap();
+    for poh in [&mut full, &mut light] {
+        poh.append_data_entry(b"b".to_vec()).unwrap();
+        poh.resume(2).unwrap();
+    }
+    let new = light.len() - 1;
+    assert_eq!(light.state[1..], full.state[full.len() - new..]);
+    assert!(light.verify().is_ok());
+
+    let other = PoHConfig { tick_interval: 11, ..config.clone() };
+    assert!(matches!(PoHUsage::from_snapshot(full.snapshot(), other, extensions), Err(PoHError::InvalidConfig(_))));
+    assert!(matches!(PoHUsage::from_snapshot(full.snapshot(), config, vec![]), Err(PoHError::InvalidConfig(_))));
+}
@@ -0,0 +1,34 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Throughput of generation runs.
+
+use std::time::Duration;
+
+/// Timing of the most recent `PoHUsage::init` run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RunStats {
+    /// Wall-clock time the run took.
+    pub elapsed: Duration,
+    /// Number of entries the run produced.
+    pub entries: usize,
+}
+
+impl RunStats {
+    /// Entries produced per second of wall-clock time.
+    /// Returns 0 for a run that took no measurable time.
+    #[must_use]
+    pub fn entries_per_second(&self) -> f64 {
+        let seconds = self.elapsed.as_secs_f64();
+        if seconds == 0.0 {
+            return 0.0;
+        }
+        self.entries as f64 / seconds
+    }
+}
+
+#[test]
+fn entries_per_second_divides_by_elapsed() {
+    let stats = RunStats { elapsed: Duration::from_millis(500), entries: 1000 };
+    assert_eq!(stats.entries_per_second(), 2000.0);
+    assert_eq!(RunStats { elapsed: Duration::ZERO, entries: 5 }.entries_per_second(), 0.0);
+}
@@ -0,0 +1,198 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Memory-bounded generation and verification of PoH chains over `std::io`.
+//!
+//! Entries are written as soon as they are generated and read back one at a
+//! time, so only the last entry is ever held in memory. Integers are
+//! little-endian and entries use the record layout of the `binary` module.
+//! Records longer than any entry the configuration can produce are rejected
+//! before they are read, see `max_record_len`.
+//!
+//! ```text
+//! magic        4 bytes   "POHS"
+//! version      u8        1
+//! output size  u32       length of every entry hash
+//! records, until the end of the stream:
+//!   length     u32       length of the encoded entry
+//!   entry      length bytes
+//! ```
+
+use std::io::{self, Read, Write};
+
+use digest::Digest;
+
+use crate::binary::{decode_entry, encode_entry, Reader};
+use crate::{InitialSeed, PoHConfig, PoHEntry, PoHUsage};
+
+/// Magic number opening every entry stream.
+pub const STREAM_MAGIC: [u8; 4] = *b"POHS";
+/// Current version of the stream format.
+pub const STREAM_VERSION: u8 = 1;
+/// Appended data a record may carry when data entries are allowed without a
+/// `max_data_len`.
+pub const MAX_STREAM_DATA_LEN: usize = 1 << 20;
+/// Room a record may use for timestamps and milestone names.
+const TEXT_ALLOWANCE: usize = 4096;
+
+impl<D: Digest + Clone> PoHUsage<D> {
+    /// Generates a chain of `count` ticks after the genesis entry derived from
+    /// `seed`, writing every entry to `writer` as soon as it exists.
+    ///
+    /// Returns the hash of the last entry. Configuration errors, including a
+    /// `count` above `max_entries`, are reported as `io::ErrorKind::InvalidInput`.
+    pub fn stream_to<W: Write>(config: PoHConfig<D>, seed: InitialSeed, count: usize, mut writer: W) -> io::Result<Vec<u8>> {
+        let poh = PoHUsage::new(config, seed, None, vec![])?;
+        poh.config.check_output_size()?;
+        poh.ensure_capacity(count)?;
+
+        writer.write_all(&STREAM_MAGIC)?;
+        writer.write_all(&[STREAM_VERSION])?;
+        writer.write_all(&(poh.config.hash_len() as u32).to_le_bytes())?;
+
+        let mut record = Vec::new();
+        let genesis = &poh.state[0];
+        write_record(&mut writer, &mut record, genesis)?;
+        let mut last_hash = genesis.hash.clone();
+        for entry in poh.iter_ticks().take(count) {
+            write_record(&mut writer, &mut record, &entry)?;
+            last_hash = entry.hash;
+        }
+        writer.flush()?;
+        Ok(last_hash.into_vec())
+    }
+
+    /// Reads a stream produced by `stream_to` and verifies every link as it
+    /// arrives, without holding the chain in memory.
+    ///
+    /// Returns the hash of the last entry. Malformed streams and failed links are
+    /// reported as `io::ErrorKind::InvalidData`.
+    pub fn stream_verify<R: Read>(config: &PoHConfig<D>, mut reader: R) -> io::Result<Vec<u8>> {
+        let mut header = [0u8; 9];
+        reader.read_exact(&mut header)?;
+        if header[..4] != STREAM_MAGIC {
+            return Err(invalid_data("missing stream magic number"));
+        }
+        if header[4] != STREAM_VERSION {
+            return Err(invalid_data("unsupported stream version"));
+        }
+        let output_size = u32::from_le_bytes(header[5..].try_into().expect("four bytes")) as usize;
+        if output_size != config.hash_len() {
+            return Err(invalid_data("stream hash length does not match the configuration"));
+        }
+
+        let max_len = max_record_len(config);
+        let mut record = Vec::new();
+        let mut prev = match read_record(&mut reader, &mut record, output_size, max_len)? {
+            Some(genesis) => genesis,
+            None => return Err(invalid_data("stream has no entries")),
+        };
+        while let Some(entry) = read_record(&mut reader, &mut record, output_size, max_len)? {
+            Self::verify_link(&prev, &entry, config).map_err(invalid_data)?;
+            prev = entry;
+        }
+        Ok(prev.hash.into_vec())
+    }
+}
+
+fn write_record<W: Write>(writer: &mut W, record: &mut Vec<u8>, entry: &PoHEntry) -> io::Result<()> {
+    record.clear();
+    encode_entry(record, entry)?;
+    let len = u32::try_from(record.len()).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "entry record longer than u32::MAX bytes"))?;
+    writer.write_all(&len.to_le_bytes())?;
+    writer.write_all(record)
+}
+
+/// Longest record an entry under `config` can be encoded to: the fixed-size
+/// fields, the hash and previous hash, a hex fingerprint of the hash, appended
+/// data up to `max_data_len` (or `MAX_STREAM_DATA_LEN`) and `TEXT_ALLOWANCE`.
+fn max_record_len<D: Digest + Clone>(config: &PoHConfig<D>) -> usize {
+    let hash = config.hash_len();
+    let data = if config.allow_data_entries { config.max_data_len.unwrap_or(MAX_STREAM_DATA_LEN) } else { 0 };
+    // id, flags, extended flags, interval, timestamp, lane and nonce
+    let fixed = 8 + 1 + 1 + 8 + 8 + 1 + 8;
+    fixed + hash + (4 + hash) + (4 + 2 * hash) + (4 + data) + TEXT_ALLOWANCE
+}
+
+/// Reads the next record, or `None` at a clean end of the stream. Records
+/// longer than `max_len` are rejected without allocating them.
+fn read_record<R: Read>(reader: &mut R, record: &mut Vec<u8>, output_size: usize, max_len: usize) -> io::Result<Option<PoHEntry>> {
+    let mut len = [0u8; 4];
+    let mut filled = 0;
+    while filled < len.len() {
+        match reader.read(&mut len[filled..]) {
+            Ok(0) if filled == 0 => return Ok(None),
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(n) => filled += n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    let len = u32::from_le_bytes(len) as usize;
+    if len > max_len {
+        return Err(invalid_data("entry record is longer than any entry of the configuration"));
+    }
+    record.resize(len, 0);
+    reader.read_exact(record)?;
+
+    let mut cursor = Reader { bytes: record };
+    let entry = decode_entry(&mut cursor, output_size).map_err(invalid_data)?;
+    if !cursor.bytes.is_empty() {
+        return Err(invalid_data("trailing bytes in entry record"));
+    }
+    Ok(Some(entry))
+}
+
+fn invalid_data<E: Into<Box<dyn std::error::Error + Send + Sync>>>(err: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+#[test]
+fn streamed_chains_match_init_and_verify() {
+    use crate::TickEntryType;
+
+    let config = PoHConfig::new(sha2::Sha256::new(), 32, 10, Some(20), true, true, TickEntryType::Data);
+    let seed = InitialSeed([17; 64]);
+    let mut bytes = Vec::new();
+    let last = PoHUsage::stream_to(config.clone(), seed.clone(), 20, &mut bytes).unwrap();
+
+    let mut poh = PoHUsage::new(config.clone(), seed.clone(), None, vec![]).unwrap();
+    poh.init().unwrap();
+    assert_eq!(last, poh.last().unwrap().hash);
+    assert_eq!(PoHUsage::stream_verify(&config, &bytes[..]).unwrap(), last);
+
+    let err = PoHUsage::stream_to(config.clone(), seed, 21, io::sink()).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+    let mut tampered = bytes.clone();
+    // The final bytes of the stream are the hash of the last entry.
+    let last_byte = tampered.len() - 1;
+    tampered[last_byte] ^= 1;
+    let err = PoHUsage::stream_verify(&config, &tampered[..]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+    let err = PoHUsage::stream_verify(&config, &bytes[..bytes.len() - 1]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn oversized_records_are_rejected_before_reading() {
+    use crate::TickEntryType;
+
+    let mut config = PoHConfig::new(sha2::Sha256::new(), 32, 10, Some(4), true, true, TickEntryType::Data);
+    config.max_data_len = Some(64);
+    let mut bytes = Vec::new();
+    PoHUsage::stream_to(config.clone(), InitialSeed([80; 64]), 4, &mut bytes).unwrap();
+    assert!(PoHUsage::stream_verify(&config, &bytes[..]).is_ok());
+
+    let mut oversized = bytes[..9].to_vec();
+    oversized.extend_from_slice(&u32::MAX.to_le_bytes());
+    let err = PoHUsage::stream_verify(&config, &oversized[..]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+    let mut just_over = bytes[..9].to_vec();
+    just_over.extend_from_slice(&(max_record_len(&config) as u32 + 1).to_le_bytes());
+    just_over.resize(just_over.len() + max_record_len(&config) + 1, 0);
+    let err = PoHUsage::stream_verify(&config, &just_over[..]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert!(err.to_string().contains("longer than any entry"));
+}
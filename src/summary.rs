@@ -0,0 +1,71 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Aggregate overview of a PoH chain.
+
+use alloc::vec::Vec;
+
+use digest::Digest;
+
+use crate::extensions::Extensions;
+use crate::PoHUsage;
+
+/// Snapshot of a chain, as returned by `PoHUsage::summary`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ChainSummary {
+    /// Number of entries, including the genesis entry.
+    pub entries: usize,
+    /// Sequential hashes backing the entries, see `PoHUsage::total_work`.
+    pub total_work: u64,
+    /// Hash of the genesis entry.
+    pub genesis_hash: Vec<u8>,
+    /// Hash of the most recent entry.
+    pub tip_hash: Vec<u8>,
+    /// Number of entries carrying appended data.
+    pub data_entries: usize,
+    /// Active extensions.
+    pub extensions: Vec<Extensions>,
+    /// Earliest and latest Unix timestamps recorded on the entries, if any.
+    pub time_span: Option<(u64, u64)>,
+}
+
+impl<D: Digest + Clone> PoHUsage<D> {
+    /// Collects the headline figures of the chain in a single pass.
+    #[must_use]
+    pub fn summary(&self) -> ChainSummary {
+        let timestamps = self.state.iter().filter_map(|entry| entry.timestamp);
+        let time_span = timestamps.fold(None, |span, timestamp| match span {
+            None => Some((timestamp, timestamp)),
+            Some((first, last)) => Some((first.min(timestamp), last.max(timestamp))),
+        });
+        ChainSummary {
+            entries: self.state.len(),
+            total_work: self.total_work(),
+            genesis_hash: self.genesis_hash().to_vec(),
+            tip_hash: self.last().expect("PoH state always holds the genesis entry").hash.to_vec(),
+            data_entries: self.count_data_entries(),
+            extensions: self.extensions.clone(),
+            time_span,
+        }
+    }
+}
+
+#[test]
+fn summary_aggregates_the_chain() {
+    use crate::{InitialSeed, PoHConfig, TickEntryType};
+
+    let config = PoHConfig::new(sha2::Sha256::new(), 32, 10, Some(4), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed([50; 64]), Some(b"init".to_vec()), vec![Extensions::MerkleTree]).unwrap();
+    poh.append_data_entry(b"event".to_vec()).unwrap();
+    poh.resume(2).unwrap();
+    poh.state[1].timestamp = Some(200);
+    poh.state[3].timestamp = Some(150);
+
+    let summary = poh.summary();
+    assert_eq!(summary.entries, 4);
+    assert_eq!(summary.total_work, 31);
+    assert_eq!(summary.genesis_hash, poh.state[0].hash);
+    assert_eq!(summary.tip_hash, poh.state[3].hash);
+    assert_eq!(summary.data_entries, 2);
+    assert_eq!(summary.extensions, vec![Extensions::MerkleTree]);
+    assert_eq!(summary.time_span, Some((150, 200)));
+}
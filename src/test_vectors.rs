@@ -0,0 +1,87 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Canonical SHA-256 test vectors.
+//!
+//! Every vector pairs a seed and configuration with the entries the crate
+//! generates from them, hashes included. The hashes are fixed constants, so an
+//! independent implementation can check that it produces the same chains byte
+//! for byte. Vectors are only ever added, never changed.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use digest::Digest;
+use sha2::Sha256;
+
+use crate::{AppendedData, EntryHash, InitialSeed, PoHConfig, PoHEntry, TickEntryType};
+
+/// The canonical vectors:
+///
+/// 1. Three ticks of a single round each from an all-zero seed.
+/// 2. Ten rounds per entry from an all-one seed with initial data `genesis`,
+///    followed by a data entry `hello` with nonce 0 and two ticks.
+/// 3. A hundred rounds per entry with hashes truncated to 16 bytes, from a seed
+///    of `0x2a` bytes.
+pub fn official_vectors() -> Vec<(InitialSeed, PoHConfig<Sha256>, Vec<PoHEntry>)> {
+    vec![
+        (
+            InitialSeed([0; 64]),
+            PoHConfig::new(Sha256::new(), 32, 1, Some(3), true, true, TickEntryType::Data),
+            vec![
+                entry(32, 0, "f5a5fd42d16a20302798ef6ed309979b43003d2320d9f0e8ea9831a92759fb4b", None, None),
+                entry(32, 1, "e2f61c3f71d1defd3fa999dfa36953755c690689799962b48bebd836974e8cf9", None, None),
+                entry(32, 2, "4400c23b6f78f68de47b5cd5f399c7c59122691ae543f6c81df5780225d5f03a", None, None),
+                entry(32, 3, "e94732d258b30828840b6ddeb13931ca9cac4c509da710f517e3d7a1d9dbbf18", None, None),
+            ],
+        ),
+        (
+            InitialSeed([1; 64]),
+            PoHConfig::new(Sha256::new(), 32, 10, Some(3), true, true, TickEntryType::Data),
+            vec![
+                entry(32, 0, "1dd69202362f9b7db11db4386eeeb0fb5afdd6f5bdb019d5680b3dc85aeb4e0a", Some(b"genesis"), None),
+                entry(32, 1, "402ded1fb10798922d2a6be347cfc3436f78b680f21b1c421f156e7200f0ec05", Some(b"hello"), Some(0)),
+                entry(32, 2, "838cc5febd5cf68daf354cf320996760a1914663682fcee8b19b4c5cf33d3c4f", None, None),
+                entry(32, 3, "73c394586a292cadd8814ef1e44b4321931686de5b49817018521663607782e8", None, None),
+            ],
+        ),
+        (
+            InitialSeed([0x2a; 64]),
+            PoHConfig::new(Sha256::new(), 16, 100, Some(2), true, true, TickEntryType::Data),
+            vec![
+                entry(16, 0, "4ac5b1038ff9a79b5ed7a8cbbb97f2b6", None, None),
+                entry(16, 1, "dc7d6804f7115591f415ce03362a5a01", None, None),
+                entry(16, 2, "409a0c2fa30ad76dccf200091e4cc077", None, None),
+            ],
+        ),
+    ]
+}
+
+fn entry(hash_len: usize, id: u64, hash: &str, data: Option<&[u8]>, nonce: Option<u64>) -> PoHEntry {
+    let hash = hex::decode(hash).expect("vector hashes are valid hex");
+    PoHEntry {
+        id,
+        hash: EntryHash::new(hash, hash_len).expect("vector hashes have the configured length"),
+        appended_data: data.map(|data| AppendedData::new(data.to_vec())),
+        nonce,
+        ..PoHEntry::default()
+    }
+}
+
+#[test]
+fn official_vectors_match_generated_chains() {
+    use crate::PoHUsage;
+
+    for (seed, config, entries) in official_vectors() {
+        let init_data = entries[0].appended_data.as_ref().map(AppendedData::to_vec);
+        let mut poh = PoHUsage::new(config.clone(), seed, init_data, vec![]).unwrap();
+        for entry in &entries[1..] {
+            match &entry.appended_data {
+                Some(data) => poh.append_data_entry(data.to_vec()).map(|_| ()),
+                None => poh.resume(1),
+            }
+            .unwrap();
+        }
+        assert_eq!(poh.get_state(), &entries);
+        assert!(PoHUsage::from_state(config, entries, vec![]).is_ok());
+    }
+}
@@ -1,12 +1,32 @@
-use chrono::Utc;
+use chrono::{DateTime, ParseError, Utc};
 
 /// Represents a timestamp in the PoH (Proof of History) process.
-
-
 pub fn get_current_timestamp_rfc3339() -> String {
     Utc::now().to_rfc3339()
 }
 
 pub fn get_current_timestamp() -> u64 {
-    return Utc::now().timestamp() as u64;
-}
\ No newline at end of file
+    Utc::now().timestamp() as u64
+}
+
+/// Parses an RFC 3339 timestamp, such as one produced by
+/// `get_current_timestamp_rfc3339`, into UTC.
+pub fn parse_rfc3339(s: &str) -> Result<DateTime<Utc>, ParseError> {
+    DateTime::parse_from_rfc3339(s).map(|time| time.with_timezone(&Utc))
+}
+
+/// Converts an RFC 3339 timestamp to Unix seconds.
+/// Times before the Unix epoch saturate to 0.
+pub fn rfc3339_to_unix(s: &str) -> Result<u64, ParseError> {
+    parse_rfc3339(s).map(|time| u64::try_from(time.timestamp()).unwrap_or(0))
+}
+
+#[test]
+fn rfc3339_round_trips() {
+    let now = get_current_timestamp_rfc3339();
+    assert_eq!(parse_rfc3339(&now).unwrap().to_rfc3339(), now);
+    assert_eq!(rfc3339_to_unix("2001-09-09T01:46:40Z").unwrap(), 1_000_000_000);
+    assert_eq!(rfc3339_to_unix("2001-09-09T03:46:40+02:00").unwrap(), 1_000_000_000);
+    assert_eq!(rfc3339_to_unix("1969-12-31T23:59:59Z").unwrap(), 0);
+    assert!(parse_rfc3339("yesterday").is_err());
+}
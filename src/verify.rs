@@ -0,0 +1,615 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Verification of Proof of History (PoH) chains.
+//!
+//! A chain is verified by replaying it: starting from the first entry, every
+//! following entry is recomputed from its predecessor using the configured
+//! hasher and `tick_interval`, and compared against the stored hash.
+//!
+//! Hashes are compared in constant time, so a mismatch reveals nothing about how
+//! many leading bytes matched. The `subtle` feature uses the `subtle` crate for
+//! the comparison.
+
+use digest::Digest;
+
+use crate::errors::{PoHError, VerifyError};
+use crate::{PoHConfig, PoHEntry, PoHUsage};
+
+impl<D: Digest + Clone> PoHUsage<D> {
+    /// Replays the whole chain and checks every stored hash.
+    ///
+    /// The first entry is taken as the starting point. Each following entry is
+    /// recomputed from the previous one with `tick_interval` hash rounds, folding in
+    /// its appended data (if any) on the final round. Ids are not part of the hash,
+    /// so each one must also be one more than the id of the preceding entry, and a
+    /// recorded previous hash must match the preceding entry. The first entry that does not match is reported
+    /// in the error. Once every link matches, data entries must be at least
+    /// `min_interval_between_data` rounds apart, as they are when generated.
+    ///
+    /// Every hash must have the configured length. A chain holding a pruning
+    /// commitment must start after its genesis entry and hold its hash. The pruned entries themselves are checked against the commitment with
+    /// `verify_pruned`.
+    #[must_use = "the chain is only verified if the result is checked"]
+    pub fn verify(&self) -> Result<(), VerifyError> {
+        if self.state.is_empty() {
+            return Err(VerifyError::EmptyChain);
+        }
+        self.check_start()?;
+        self.verify_range(0, self.state.len())
+    }
+
+    /// Same as `verify`, calling `on_progress(done, total)` after every entry is
+    /// checked, where `done` counts the entries checked so far, the first one
+    /// included, out of `total`.
+    ///
+    /// Returning `false` from `on_progress` cancels verification with
+    /// `VerifyError::Cancelled`. Mismatches abort as in `verify`, without a
+    /// further call.
+    #[must_use = "the chain is only verified if the result is checked"]
+    pub fn verify_with_progress(&self, mut on_progress: impl FnMut(usize, usize) -> bool) -> Result<(), VerifyError> {
+        let total = self.state.len();
+        if total == 0 {
+            return Err(VerifyError::EmptyChain);
+        }
+        self.check_start()?;
+        if !on_progress(1, total) {
+            return Err(VerifyError::Cancelled { done: 1 });
+        }
+        for (index, pair) in self.state.windows(2).enumerate() {
+            check_successor(&self.config, &pair[0], &pair[1], index + 1)?;
+            let done = index + 2;
+            if !on_progress(done, total) {
+                return Err(VerifyError::Cancelled { done });
+            }
+        }
+        check_data_spacing(&self.config, &self.state, self.data_rounds, 0)
+    }
+
+    /// Cheap structural checks to run before the expensive replay of `verify`.
+    ///
+    /// Checks, without hashing, that the chain is not empty, that every hash has
+    /// the configured length, that ids count up by one, and that the appended data
+    /// of every entry is accepted by the configuration, as it would be when
+    /// appending it. Entries without data must be allowed by `allow_empty_entries`,
+    /// except for breaker entries.
+    #[must_use = "the chain is only checked structurally if the result is inspected"]
+    pub fn is_structurally_valid(&self) -> Result<(), VerifyError> {
+        if self.state.is_empty() {
+            return Err(VerifyError::EmptyChain);
+        }
+        let expected = self.config.hash_len();
+        let first_id = self.state[0].id;
+        for (index, entry) in self.state.iter().enumerate() {
+            if entry.hash.len() != expected {
+                return Err(VerifyError::HashLengthMismatch { index, len: entry.hash.len(), expected });
+            }
+            if first_id.checked_add(index as u64) != Some(entry.id) {
+                return Err(VerifyError::Malformed { index, reason: "id does not follow its predecessor" });
+            }
+            let data = entry.data_bytes();
+            let checked = match data.as_deref() {
+                Some(data) => self.config.check_data(data),
+                None if entry.breaker || self.config.allow_empty_entries => Ok(()),
+                None => Err(PoHError::EmptyNotAllowed),
+            };
+            let reason = match checked {
+                Ok(()) => continue,
+                Err(PoHError::DataNotAllowed) => "data entries are not allowed",
+                Err(PoHError::DataTooLarge { .. }) => "appended data exceeds max_data_len",
+                Err(PoHError::EmptyNotAllowed) => "empty entries are not allowed",
+                Err(_) => "appended data does not match the entry type",
+            };
+            return Err(VerifyError::Malformed { index, reason });
+        }
+        Ok(())
+    }
+
+    /// Same as `verify`, limited to the entries in `start..end`.
+    ///
+    /// The entry at `start` is trusted as a checkpoint, and every following entry
+    /// up to `end` is replayed from it, appended data included. The spacing of
+    /// data entries still counts the rounds before `start`. Mismatches are
+    /// reported at their index in the whole chain. Fails with `InvalidRange`
+    /// unless `start < end <= self.len()`.
+    #[must_use = "the chain is only verified if the result is checked"]
+    pub fn verify_range(&self, start: usize, end: usize) -> Result<(), VerifyError> {
+        let len = self.state.len();
+        if start >= end || end > len {
+            return Err(VerifyError::InvalidRange { start, end, len });
+        }
+
+        for (offset, pair) in self.state[start..end].windows(2).enumerate() {
+            check_successor(&self.config, &pair[0], &pair[1], start + offset + 1)?;
+        }
+        check_data_spacing(&self.config, &self.state[start..end], self.data_rounds_at(start), start)
+    }
+
+    /// Checks that stored timestamps never go backwards.
+    ///
+    /// Entries without a timestamp are skipped. The hash chain only proves the
+    /// order of entries, so this catches clocks that were tampered with or
+    /// entries stamped out of order.
+    #[must_use = "the timestamps are only checked if the result is inspected"]
+    pub fn check_timestamps_monotonic(&self) -> Result<(), VerifyError> {
+        self.check_timestamps(None)
+    }
+
+    /// Like `check_timestamps_monotonic`, additionally rejecting any timestamp more
+    /// than `max_skew` seconds after the previous one.
+    #[must_use = "the timestamps are only checked if the result is inspected"]
+    pub fn check_timestamp_skew(&self, max_skew: u64) -> Result<(), VerifyError> {
+        self.check_timestamps(Some(max_skew))
+    }
+
+    fn check_timestamps(&self, max_skew: Option<u64>) -> Result<(), VerifyError> {
+        let mut previous = None;
+        for (index, entry) in self.state.iter().enumerate() {
+            let Some(timestamp) = entry.timestamp else { continue };
+            if let Some(previous) = previous {
+                if timestamp < previous {
+                    return Err(VerifyError::TimestampRegression { index, previous, timestamp });
+                }
+                if let Some(max_skew) = max_skew.filter(|&max_skew| timestamp - previous > max_skew) {
+                    return Err(VerifyError::TimestampSkew { index, previous, timestamp, max_skew });
+                }
+            }
+            previous = Some(timestamp);
+        }
+        Ok(())
+    }
+
+    /// Checks an entry on its own, using the previous hash it records.
+    ///
+    /// Requires a chain generated with `store_prev_links`. This proves the entry
+    /// follows the recorded hash; `verify` additionally proves that hash belongs to
+    /// the preceding entry.
+    #[must_use = "the entry is only verified if the result is checked"]
+    pub fn verify_entry(entry: &PoHEntry, config: &PoHConfig<D>) -> Result<(), VerifyError> {
+        let index = entry.id as usize;
+        let prev = entry.prev.as_ref().ok_or(VerifyError::MissingPrevLink { index })?;
+        entry.verify_against(prev, config)
+    }
+
+    /// Checks a single hop of a chain: recomputes `next` from `prev.hash` and
+    /// compares it with the hash stored in `next`.
+    ///
+    /// This lets entries be validated one at a time as they arrive, without
+    /// holding the chain. The id of `next` must follow the id of `prev`. A mismatch
+    /// is reported at index `next.id`.
+    #[must_use = "the link is only verified if the result is checked"]
+    pub fn verify_link(prev: &PoHEntry, next: &PoHEntry, config: &PoHConfig<D>) -> Result<(), VerifyError> {
+        check_successor(config, prev, next, next.id as usize)
+    }
+    /// Same as `verify`, checking the links in parallel with `rayon`.
+    ///
+    /// Every entry stores everything its hash depends on, so each link can be
+    /// recomputed independently from the stored hash of its predecessor, and the
+    /// links are spread across the `rayon` thread pool. The result is identical to
+    /// `verify`: the first mismatching entry is reported.
+    #[cfg(feature = "parallel")]
+    #[must_use = "the chain is only verified if the result is checked"]
+    pub fn verify_parallel(&self) -> Result<(), VerifyError>
+    where
+        D: Send + Sync,
+    {
+        use rayon::prelude::*;
+
+        if self.state.is_empty() {
+            return Err(VerifyError::EmptyChain);
+        }
+        self.check_start()?;
+
+        self.state
+            .par_windows(2)
+            .enumerate()
+            .map(|(index, pair)| check_successor(&self.config, &pair[0], &pair[1], index + 1))
+            .find_first(Result::is_err)
+            .unwrap_or_else(|| check_data_spacing(&self.config, &self.state, self.data_rounds, 0))
+    }
+
+    /// Checks that the first entry has a hash of the configured length, and that
+    /// a chain holding a pruning commitment no longer holds the genesis entry,
+    /// which the commitment would have to cover, but still holds its hash.
+    fn check_start(&self) -> Result<(), VerifyError> {
+        let first = &self.state[0];
+        check_hash_len(&self.config, first, 0)?;
+        if self.pruned_commitment.is_some() && first.id == 0 {
+            return Err(VerifyError::Malformed { index: 0, reason: "the genesis entry is held along with a pruning commitment" });
+        }
+        if self.pruned_commitment.is_some() && self.genesis_hash.is_none() {
+            return Err(VerifyError::Malformed { index: 0, reason: "a pruned chain does not hold the genesis hash" });
+        }
+        if first.id == 0 && self.genesis_hash.as_ref().is_some_and(|hash| !hashes_equal(hash, &first.hash)) {
+            return Err(VerifyError::Malformed { index: 0, reason: "the genesis hash is not the hash of the genesis entry" });
+        }
+        Ok(())
+    }
+}
+
+/// Checks that `entry` follows `prev` in a chain: its id comes next, its hash
+/// has the configured length, and `check_link` holds.
+fn check_successor<D: Digest + Clone>(config: &PoHConfig<D>, prev: &PoHEntry, entry: &PoHEntry, index: usize) -> Result<(), VerifyError> {
+    if prev.id.checked_add(1) != Some(entry.id) {
+        return Err(VerifyError::Malformed { index, reason: "id does not follow its predecessor" });
+    }
+    check_hash_len(config, entry, index)?;
+    check_link(config, prev, entry, index)
+}
+
+/// Checks that the hash of `entry` has the length `config` produces. Hashes that
+/// were deserialized, rather than decoded under a configuration, are checked here.
+fn check_hash_len<D: Digest + Clone>(config: &PoHConfig<D>, entry: &PoHEntry, index: usize) -> Result<(), VerifyError> {
+    let expected = config.hash_len();
+    if entry.hash.len() != expected {
+        return Err(VerifyError::HashLengthMismatch { index, len: entry.hash.len(), expected });
+    }
+    Ok(())
+}
+
+/// Checks the previous hash recorded in `entry`, if any, and recomputes its hash
+/// from `prev`.
+fn check_link<D: Digest + Clone>(config: &PoHConfig<D>, prev: &PoHEntry, entry: &PoHEntry, index: usize) -> Result<(), VerifyError> {
+    if entry.prev.as_ref().is_some_and(|recorded| !hashes_equal(recorded, &prev.hash)) {
+        return Err(VerifyError::PrevLinkMismatch { index });
+    }
+    let expected = config.link_hash(&prev.hash, entry);
+    if !hashes_equal(&expected, &entry.hash) {
+        return Err(VerifyError::HashMismatch {
+            index,
+            expected,
+            actual: entry.hash.to_vec(),
+        });
+    }
+    Ok(())
+}
+
+/// Checks that every data entry after the first of `entries` ends at least
+/// `min_interval_between_data` rounds after the previous data entry, where
+/// `since` are the rounds since a data entry as of `entries[0]`. Indices are
+/// offset by `offset`. The rounds replayed for an entry are taken from the entry,
+/// so this catches data entries forged with a short `interval` that still link
+/// correctly.
+fn check_data_spacing<D: Digest + Clone>(config: &PoHConfig<D>, entries: &[PoHEntry], since: Option<u64>, offset: usize) -> Result<(), VerifyError> {
+    let min = config.min_interval_between_data;
+    if min == 0 {
+        return Ok(());
+    }
+    let mut since_data = since;
+    for (index, entry) in entries.iter().enumerate().skip(1) {
+        let rounds = since_data.map(|since: u64| since.saturating_add(config.rounds(entry)));
+        if entry.appended_data.is_some() {
+            match rounds {
+                Some(rounds) if rounds < min => return Err(VerifyError::DataTooClose { index: offset + index, rounds, min }),
+                _ => since_data = Some(0),
+            }
+        } else {
+            since_data = rounds;
+        }
+    }
+    Ok(())
+}
+
+/// Compares two hashes in constant time. Only the lengths, which are public,
+/// are compared with an early exit.
+#[cfg(feature = "subtle")]
+pub(crate) fn hashes_equal(a: &[u8], b: &[u8]) -> bool {
+    use subtle::ConstantTimeEq;
+
+    a.ct_eq(b).into()
+}
+
+/// Compares two hashes in constant time. Only the lengths, which are public,
+/// are compared with an early exit.
+#[cfg(not(feature = "subtle"))]
+pub(crate) fn hashes_equal(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let diff = a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y));
+    core::hint::black_box(diff) == 0
+}
+
+impl PoHEntry {
+    /// Compares the hashes of two entries in constant time.
+    ///
+    /// An entry's hash commits to its whole history, so this is the comparison to
+    /// use when one side may be attacker controlled.
+    #[must_use]
+    pub fn ct_eq(&self, other: &PoHEntry) -> bool {
+        hashes_equal(&self.hash, &other.hash)
+    }
+
+    /// Checks that this entry follows `prev_hash` under `config`, without a chain.
+    ///
+    /// The hash is recomputed from `prev_hash` exactly as `PoHUsage::verify` does,
+    /// and a previous hash recorded in the entry must equal `prev_hash`. A mismatch
+    /// is reported at index `self.id`.
+    #[must_use = "the entry is only verified if the result is checked"]
+    pub fn verify_against<D: Digest + Clone>(&self, prev_hash: &[u8], config: &PoHConfig<D>) -> Result<(), VerifyError> {
+        let prev = PoHEntry { hash: prev_hash.to_vec().into(), ..PoHEntry::default() };
+        check_link(config, &prev, self, self.id as usize)
+    }
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn parallel_verify_matches_sequential() {
+    use crate::{InitialSeed, TickEntryType};
+
+    let config = PoHConfig::new(sha2::Sha256::new(), 32, 10, Some(50), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed([13; 64]), None, vec![]).unwrap();
+    poh.init().unwrap();
+    assert_eq!(poh.verify_parallel(), Ok(()));
+
+    poh.state[40].hash[0] ^= 1;
+    poh.state[20].hash[0] ^= 1;
+    assert_eq!(poh.verify_parallel(), poh.verify());
+    assert!(matches!(poh.verify_parallel(), Err(VerifyError::HashMismatch { index: 20, .. })));
+    assert_eq!(PoHUsage::new_unchecked(poh.config, InitialSeed([0; 64]), None, vec![]).verify_parallel(), Ok(()));
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn parallel_verify_checks_prev_links() {
+    use crate::{InitialSeed, TickEntryType};
+
+    let mut config = PoHConfig::new(sha2::Sha256::new(), 32, 10, Some(20), true, true, TickEntryType::Data);
+    config.store_prev_links = true;
+    let mut poh = PoHUsage::new(config, InitialSeed([79; 64]), None, vec![]).unwrap();
+    poh.init().unwrap();
+    poh.state[12].prev.as_mut().unwrap()[0] ^= 1;
+    assert_eq!(poh.verify(), Err(VerifyError::PrevLinkMismatch { index: 12 }));
+    assert_eq!(poh.verify_parallel(), poh.verify());
+}
+
+#[test]
+fn verify_link_checks_entries_as_they_arrive() {
+    use crate::{InitialSeed, TickEntryType};
+    use sha2::Sha256;
+
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(4), true, true, TickEntryType::Data);
+    let mut producer = PoHUsage::new(config.clone(), InitialSeed([14; 64]), None, vec![]).unwrap();
+    producer.append_data_entry(b"tx".to_vec()).unwrap();
+    producer.resume(3).unwrap();
+
+    // A consumer only keeps the last entry it has accepted.
+    let mut stream = producer.state.clone().into_iter();
+    let mut tip = stream.next().unwrap();
+    for entry in stream {
+        assert_eq!(PoHUsage::verify_link(&tip, &entry, &config), Ok(()));
+        tip = entry;
+    }
+
+    let mut forged = producer.state[1].clone();
+    forged.appended_data = Some(crate::AppendedData::new(b"other".to_vec()));
+    assert!(matches!(
+        PoHUsage::verify_link(&producer.state[0], &forged, &config),
+        Err(VerifyError::HashMismatch { index: 1, .. })
+    ));
+}
+
+#[test]
+fn timestamps_must_not_go_backwards() {
+    use crate::{InitialSeed, TickEntryType};
+    use sha2::Sha256;
+
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(4), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed([18; 64]), None, vec![]).unwrap();
+    poh.init().unwrap();
+    for (entry, timestamp) in poh.state.iter_mut().zip([100, 100, 101, 103, 200]) {
+        entry.timestamp = Some(timestamp);
+    }
+    poh.state[2].timestamp = None;
+    assert_eq!(poh.check_timestamps_monotonic(), Ok(()));
+    assert_eq!(poh.check_timestamp_skew(97), Ok(()));
+    assert_eq!(
+        poh.check_timestamp_skew(10),
+        Err(VerifyError::TimestampSkew { index: 4, previous: 103, timestamp: 200, max_skew: 10 })
+    );
+
+    poh.state[3].timestamp = Some(99);
+    assert_eq!(
+        poh.check_timestamps_monotonic(),
+        Err(VerifyError::TimestampRegression { index: 3, previous: 100, timestamp: 99 })
+    );
+}
+
+#[test]
+fn stored_prev_links_allow_spot_checks() {
+    use crate::InitialSeed;
+    use sha2::Sha256;
+
+    let config = PoHConfig::builder(Sha256::new()).tick_interval(10).max_entries(Some(4)).store_prev_links(true).build().unwrap();
+    let mut poh = PoHUsage::new(config.clone(), InitialSeed([22; 64]), None, vec![]).unwrap();
+    poh.init().unwrap();
+    assert_eq!(poh.state[0].prev, None);
+    assert!(poh.state.windows(2).all(|pair| pair[1].prev.as_deref() == Some(&*pair[0].hash)));
+    assert!(poh.verify().is_ok());
+    assert_eq!(PoHUsage::verify_entry(&poh.state[3], &config), Ok(()));
+    assert_eq!(PoHUsage::verify_entry(&poh.state[0], &config), Err(VerifyError::MissingPrevLink { index: 0 }));
+
+    poh.state[2].prev = Some(vec![0; 32]);
+    assert_eq!(poh.verify(), Err(VerifyError::PrevLinkMismatch { index: 2 }));
+    assert!(matches!(PoHUsage::verify_entry(&poh.state[2], &config), Err(VerifyError::HashMismatch { index: 2, .. })));
+
+    let restored = PoHUsage::from_bytes(&poh.to_bytes().unwrap(), config).unwrap();
+    assert_eq!(restored.state, poh.state);
+}
+
+#[test]
+fn hashes_compare_in_constant_time() {
+    assert!(hashes_equal(&[1, 2, 3], &[1, 2, 3]));
+    assert!(!hashes_equal(&[1, 2, 3], &[1, 2, 4]));
+    assert!(!hashes_equal(&[1, 2, 3], &[1, 2]));
+    assert!(hashes_equal(&[], &[]));
+
+    let entry = PoHEntry { hash: vec![7; 32].into(), ..PoHEntry::default() };
+    assert!(entry.ct_eq(&PoHEntry { id: 3, ..entry.clone() }));
+    assert!(!entry.ct_eq(&PoHEntry { hash: vec![8; 32].into(), ..entry.clone() }));
+}
+
+#[test]
+fn entries_verify_against_a_claimed_predecessor() {
+    use crate::{InitialSeed, TickEntryType};
+    use sha2::Sha256;
+
+    let config = PoHConfig::new(Sha256::new(), 32, 10, Some(3), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config.clone(), InitialSeed([41; 64]), None, vec![]).unwrap();
+    poh.append_data_entry(b"event".to_vec()).unwrap();
+    poh.resume(1).unwrap();
+
+    let entry = &poh.state[2];
+    assert_eq!(entry.verify_against(&poh.state[1].hash, &config), Ok(()));
+    assert!(matches!(entry.verify_against(&poh.state[0].hash, &config), Err(VerifyError::HashMismatch { index: 2, .. })));
+    assert_eq!(poh.state[1].verify_against(&poh.state[0].hash, &config), Ok(()));
+}
+
+#[test]
+fn verify_range_trusts_its_first_entry() {
+    use crate::{InitialSeed, TickEntryType};
+
+    let config = PoHConfig::new(sha2::Sha256::new(), 32, 10, Some(8), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed([58; 64]), None, vec![]).unwrap();
+    poh.resume(3).unwrap();
+    poh.append_data_entry(b"tx".to_vec()).unwrap();
+    poh.resume(4).unwrap();
+    assert_eq!(poh.verify_range(0, 9), Ok(()));
+
+    poh.state[2].hash[0] ^= 1;
+    assert!(matches!(poh.verify(), Err(VerifyError::HashMismatch { index: 2, .. })));
+    assert!(matches!(poh.verify_range(1, 5), Err(VerifyError::HashMismatch { index: 2, .. })));
+    assert_eq!(poh.verify_range(3, 9), Ok(()));
+    assert_eq!(poh.verify_range(2, 3), Ok(()));
+    assert!(matches!(poh.verify_range(2, 4), Err(VerifyError::HashMismatch { index: 3, .. })));
+
+    poh.state[4].appended_data = Some(crate::AppendedData::new(b"forged".to_vec()));
+    assert!(matches!(poh.verify_range(3, 9), Err(VerifyError::HashMismatch { index: 4, .. })));
+    assert_eq!(poh.verify_range(5, 9), Ok(()));
+
+    assert_eq!(poh.verify_range(4, 4), Err(VerifyError::InvalidRange { start: 4, end: 4, len: 9 }));
+    assert_eq!(poh.verify_range(0, 10), Err(VerifyError::InvalidRange { start: 0, end: 10, len: 9 }));
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn generated_chains_always_verify(
+        seed in proptest::collection::vec(proptest::prelude::any::<u8>(), 64),
+        output_size in 1usize..=32,
+        tick_interval in 1u64..20,
+        data_mix in proptest::sample::select(vec![crate::DataMixMode::Prefix, crate::DataMixMode::Suffix, crate::DataMixMode::Interleaved]),
+        big_endian in proptest::prelude::any::<bool>(),
+        store_prev_links in proptest::prelude::any::<bool>(),
+        items in proptest::collection::vec(proptest::option::of(proptest::collection::vec(proptest::prelude::any::<u8>(), 0..16)), 0..8),
+    ) {
+        use crate::{Endianness, InitialSeed, TickEntryType};
+
+        let endianness = if big_endian { Endianness::Big } else { Endianness::Little };
+        let config = PoHConfig::builder(sha2::Sha256::new())
+            .output_size(output_size)
+            .tick_interval(tick_interval)
+            .max_entries(None)
+            .data_mix(data_mix)
+            .endianness(endianness)
+            .store_prev_links(store_prev_links)
+            .tick_entry_type(TickEntryType::Data)
+            .build()
+            .unwrap();
+        let mut poh = PoHUsage::new(config, InitialSeed(seed.try_into().unwrap()), None, vec![]).unwrap();
+        for item in items {
+            match item {
+                Some(data) => poh.append_data_entry(data).map(|_| ()),
+                None => poh.resume(1),
+            }
+            .unwrap();
+        }
+        proptest::prop_assert_eq!(poh.verify(), Ok(()));
+    }
+}
+
+#[test]
+fn verification_reports_progress_and_can_be_cancelled() {
+    use alloc::vec::Vec;
+
+    use crate::{InitialSeed, TickEntryType};
+
+    let config = PoHConfig::new(sha2::Sha256::new(), 32, 10, Some(5), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, InitialSeed([69; 64]), None, vec![]).unwrap();
+    poh.init().unwrap();
+
+    let mut calls = Vec::new();
+    assert_eq!(poh.verify_with_progress(|done, total| { calls.push((done, total)); true }), Ok(()));
+    assert_eq!(calls, (1..=6).map(|done| (done, 6)).collect::<Vec<_>>());
+
+    assert_eq!(poh.verify_with_progress(|done, _| done < 3), Err(VerifyError::Cancelled { done: 3 }));
+
+    poh.state[4].hash[0] ^= 1;
+    let mut last = 0;
+    assert!(matches!(poh.verify_with_progress(|done, _| { last = done; true }), Err(VerifyError::HashMismatch { index: 4, .. })));
+    assert_eq!(last, 4);
+}
+
+#[test]
+fn structural_checks_catch_malformed_chains() {
+    use crate::{AppendedData, InitialSeed, TickEntryType};
+
+    let config = PoHConfig::builder(sha2::Sha256::new()).tick_interval(10).max_entries(Some(4)).max_data_len(Some(4)).build().unwrap();
+    let mut poh = PoHUsage::new(config, InitialSeed([73; 64]), None, vec![]).unwrap();
+    poh.append_data_entry(b"tx".to_vec()).unwrap();
+    poh.resume(2).unwrap();
+    assert_eq!(poh.is_structurally_valid(), Ok(()));
+
+    let mut short = poh.clone();
+    short.state[2].hash = vec![0; 31].into();
+    assert_eq!(short.is_structurally_valid(), Err(VerifyError::HashLengthMismatch { index: 2, len: 31, expected: 32 }));
+    assert_eq!(short.verify(), Err(VerifyError::HashLengthMismatch { index: 2, len: 31, expected: 32 }));
+    short.state[0].hash = vec![0; 33].into();
+    assert_eq!(short.verify(), Err(VerifyError::HashLengthMismatch { index: 0, len: 33, expected: 32 }));
+
+    let mut gap = poh.clone();
+    gap.state[3].id = 4;
+    assert_eq!(gap.is_structurally_valid(), Err(VerifyError::Malformed { index: 3, reason: "id does not follow its predecessor" }));
+
+    let mut large = poh.clone();
+    large.state[1].appended_data = Some(AppendedData::new(b"too large".to_vec()));
+    assert_eq!(large.is_structurally_valid(), Err(VerifyError::Malformed { index: 1, reason: "appended data exceeds max_data_len" }));
+
+    let mut empty = poh.clone();
+    empty.config.allow_empty_entries = false;
+    assert_eq!(empty.is_structurally_valid(), Err(VerifyError::Malformed { index: 0, reason: "empty entries are not allowed" }));
+
+    let mut typed = poh;
+    typed.config.tick_entry_type = TickEntryType::Empty;
+    assert_eq!(typed.is_structurally_valid(), Err(VerifyError::Malformed { index: 1, reason: "appended data does not match the entry type" }));
+    typed.state.clear();
+    assert_eq!(typed.is_structurally_valid(), Err(VerifyError::EmptyChain));
+}
+
+#[test]
+fn verification_enforces_data_spacing() {
+    use crate::InitialSeed;
+
+    let config = PoHConfig::builder(sha2::Sha256::new()).tick_interval(10).max_entries(Some(8)).min_interval_between_data(35).build().unwrap();
+    let mut poh = PoHUsage::new(config, InitialSeed([81; 64]), None, vec![]).unwrap();
+    poh.append_data_entry(b"a".to_vec()).unwrap();
+    poh.resume(1).unwrap();
+    poh.append_data_entry(b"b".to_vec()).unwrap();
+    poh.resume(1).unwrap();
+    assert_eq!(poh.state[3].interval, 25);
+    assert_eq!(poh.verify(), Ok(()));
+
+    // Rehash the chain from a data entry that claims a single round.
+    poh.state[3].interval = 1;
+    for index in 3..poh.state.len() {
+        let hash = poh.config.link_hash(&poh.state[index - 1].hash, &poh.state[index]);
+        poh.state[index].hash = hash.into();
+    }
+    let expected = Err(VerifyError::DataTooClose { index: 3, rounds: 11, min: 35 });
+    assert_eq!(poh.verify(), expected);
+    assert_eq!(poh.verify_with_progress(|_, _| true), expected);
+    assert_eq!(poh.verify_range(1, 5), expected);
+    assert_eq!(poh.verify_range(2, 5), expected);
+    assert_eq!(poh.verify_range(3, 5), Ok(()));
+    #[cfg(feature = "parallel")]
+    assert_eq!(poh.verify_parallel(), expected);
+}
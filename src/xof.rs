@@ -0,0 +1,111 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Extendable-output functions (XOFs) such as SHAKE128 and SHAKE256.
+//!
+//! `XofDigest<X, N>` reads `N` bytes from the XOF `X` and implements `Digest`, so
+//! it can be used anywhere a fixed-output hasher is expected:
+//!
+//! ```
+//! use poh_yugen::xof::XofDigest;
+//! use poh_yugen::{InitialSeed, PoHConfig, PoHUsage, TickEntryType};
+//! use digest::{typenum::U20, Digest};
+//! use sha3::Shake256;
+//!
+//! let config = PoHConfig::new(XofDigest::<Shake256, U20>::new(), 20, 100, Some(10), true, true, TickEntryType::Data);
+//! let mut poh = PoHUsage::new(config, InitialSeed([0; 64]), None, vec![]).unwrap();
+//! poh.init().unwrap();
+//! assert!(poh.get_state().iter().all(|entry| entry.hash.len() == 20));
+//! ```
+//!
+//! XOF output is prefix-consistent, so an `output_size` below `N` yields exactly
+//! the bytes a native read of `output_size` bytes would.
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use digest::generic_array::ArrayLength;
+use digest::{ExtendableOutput, FixedOutput, HashMarker, Output, OutputSizeUser, Update, XofReader};
+
+/// Adapts the XOF `X` into a `Digest` with an `N` byte output.
+pub struct XofDigest<X, N> {
+    xof: X,
+    size: PhantomData<N>,
+}
+
+impl<X: Default, N> XofDigest<X, N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<X: Default, N> Default for XofDigest<X, N> {
+    fn default() -> Self {
+        Self { xof: X::default(), size: PhantomData }
+    }
+}
+
+impl<X: Clone, N> Clone for XofDigest<X, N> {
+    fn clone(&self) -> Self {
+        Self { xof: self.xof.clone(), size: PhantomData }
+    }
+}
+
+impl<X: fmt::Debug, N: ArrayLength<u8>> fmt::Debug for XofDigest<X, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("XofDigest").field("xof", &self.xof).field("output_size", &N::USIZE).finish()
+    }
+}
+
+impl<X, N: ArrayLength<u8> + 'static> OutputSizeUser for XofDigest<X, N> {
+    type OutputSize = N;
+}
+
+impl<X: Update, N> Update for XofDigest<X, N> {
+    fn update(&mut self, data: &[u8]) {
+        self.xof.update(data);
+    }
+}
+
+impl<X: ExtendableOutput, N: ArrayLength<u8> + 'static> FixedOutput for XofDigest<X, N> {
+    fn finalize_into(self, out: &mut Output<Self>) {
+        self.xof.finalize_xof().read(out);
+    }
+}
+
+impl<X, N> HashMarker for XofDigest<X, N> {}
+
+#[test]
+fn xof_entries_read_output_size_bytes() {
+    use crate::{InitialSeed, PoHConfig, PoHUsage, TickEntryType};
+    use digest::typenum::{U32, U64};
+    use digest::Digest;
+    use sha3::{Shake128, Shake256};
+
+    fn native<X: Default + Update + ExtendableOutput>(parts: &[&[u8]], len: usize) -> alloc::vec::Vec<u8> {
+        let mut xof = X::default();
+        for part in parts {
+            xof.update(part);
+        }
+        let mut out = alloc::vec![0u8; len];
+        xof.finalize_xof().read(&mut out);
+        out
+    }
+
+    let seed = InitialSeed([24; 64]);
+    let config = PoHConfig::new(XofDigest::<Shake256, U64>::new(), 48, 1, Some(3), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, seed.clone(), None, vec![]).unwrap();
+    poh.init().unwrap();
+    assert_eq!(poh.get_state()[0].hash, native::<Shake256>(&[&seed.0], 48));
+    assert!(poh.get_state().iter().all(|entry| entry.hash.len() == 48));
+    assert!(poh.verify().is_ok());
+
+    let config = PoHConfig::new(XofDigest::<Shake128, U32>::new(), 32, 1, Some(3), true, true, TickEntryType::Data);
+    let mut poh = PoHUsage::new(config, seed.clone(), None, vec![]).unwrap();
+    poh.init().unwrap();
+    let genesis = native::<Shake128>(&[&seed.0], 32);
+    assert_eq!(poh.get_state()[0].hash, genesis);
+    assert_eq!(poh.get_state()[1].hash, native::<Shake128>(&[&genesis], 32));
+    assert!(poh.verify().is_ok());
+
+    assert_eq!(<XofDigest<Shake128, U64> as Digest>::output_size(), 64);
+}